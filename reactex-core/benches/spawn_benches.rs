@@ -0,0 +1,69 @@
+#![feature(test)]
+
+extern crate test;
+
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_macro::EcsBundle;
+use reactex_macro::EcsComponent;
+use test::Bencher;
+
+#[allow(dead_code)]
+#[derive(EcsComponent, Debug)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[allow(dead_code)]
+#[derive(EcsComponent, Debug)]
+struct Velocity {
+    dx: f32,
+    dy: f32,
+}
+
+#[derive(EcsBundle)]
+struct Particle {
+    position: Position,
+    velocity: Velocity,
+}
+
+const COUNT: usize = 10_000;
+
+fn particle(i: usize) -> Particle {
+    Particle {
+        position: Position {
+            x: i as f32,
+            y: 0.0,
+        },
+        velocity: Velocity { dx: 1.0, dy: 1.0 },
+    }
+}
+
+fn container() -> EcsContainer {
+    EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_appear_handler("moving", ecs_filter!(Position, Velocity), |_, _| {});
+        })
+        .seal()
+}
+
+#[bench]
+fn spawn_one_by_one(b: &mut Bencher) {
+    b.iter(|| {
+        let mut ecs = container();
+        ecs.execute_once("spawn", |ctx| {
+            for i in 0..COUNT {
+                ctx.create_entity().add_bundle(particle(i));
+            }
+        });
+    });
+}
+
+#[bench]
+fn spawn_batch(b: &mut Bencher) {
+    b.iter(|| {
+        let mut ecs = container();
+        ecs.execute_once("spawn", |ctx| ctx.spawn_batch((0..COUNT).map(particle)));
+    });
+}
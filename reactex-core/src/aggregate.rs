@@ -0,0 +1,52 @@
+use crate::component::EcsComponent;
+use crate::filter::FilterDesc;
+use crate::internal::aggregate_storage::Aggregate;
+use crate::World;
+use std::mem;
+use std::panic::RefUnwindSafe;
+
+/// Value folded over entities of a filter and kept up to date incrementally
+/// (see `ConfigurableWorld::add_aggregate`), read with `Ctx::aggregate`.
+pub trait EcsAggregate: Default + RefUnwindSafe + 'static {
+    /// Contribution of a single entity, e.g. `()` for counts or a field value for sums.
+    type Item: RefUnwindSafe + 'static;
+
+    fn add(&mut self, item: &Self::Item);
+
+    /// Reverts `add`. Returns `false` if it can't be done incrementally (e.g. for min/max),
+    /// then the aggregate is folded again from the remaining items.
+    fn remove(&mut self, item: &Self::Item) -> bool;
+}
+
+impl World {
+    pub(crate) fn add_aggregate<A: EcsAggregate, C: EcsComponent>(
+        &mut self,
+        filter: FilterDesc,
+        extract: impl Fn(&C) -> A::Item + RefUnwindSafe + 'static,
+    ) {
+        assert!(
+            filter.component_types.contains(&C::get_component_type()),
+            "aggregated component {} is not in the filter {}",
+            C::NAME,
+            filter
+        );
+        let entities = self.existing_entities(filter.component_types);
+        let filter = self.stable.filter_manager.get_filter_mut(filter);
+        filter.track_appear_events();
+        filter.track_disappear_events();
+        let filter_key = filter.unique_key;
+        let mut aggregates = mem::take(&mut self.stable.aggregates);
+        aggregates.add::<A>(
+            filter_key,
+            C::get_component_type(),
+            Box::new(Aggregate {
+                value: A::default(),
+                items: Default::default(),
+                extract: Box::new(extract),
+            }),
+            self.immutable.gates.configured_module,
+        );
+        aggregates.on_appear(filter_key, entities.iter(), &self.stable);
+        self.stable.aggregates = aggregates;
+    }
+}
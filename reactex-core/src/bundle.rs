@@ -0,0 +1,136 @@
+use crate::component::ComponentType;
+use crate::component::EcsComponent;
+use crate::internal::cause::Cause;
+use crate::internal::change_buffer::TempEntityKey;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::entity_storage::EntityStorage;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::ExecutionResult;
+use crate::StableWorld;
+use crate::VolatileWorld;
+use crate::World;
+use std::any::Any;
+use std::panic::RefUnwindSafe;
+
+/// Group of components added and removed together (see `#[derive(EcsBundle)]`).
+pub trait EcsBundle: RefUnwindSafe + 'static {
+    const COMPONENT_TYPES: &'static [ComponentType];
+
+    fn get_component(&self, component_type: ComponentType) -> Option<&dyn Any>;
+
+    fn add_to(self, target: &mut impl BundleTarget);
+}
+
+pub trait BundleTarget {
+    fn add<T: EcsComponent>(&mut self, value: T);
+}
+
+// bundle is boxed once as a whole, its components are moved into typed pools on apply
+pub(crate) trait AbstractBundle {
+    fn component_types(&self) -> &'static [ComponentType];
+
+    fn get_component(&self, component_type: ComponentType) -> Option<&dyn Any>;
+
+    fn add_to(self: Box<Self>, entity: InternalEntityKey, volatile: &mut VolatileWorld);
+}
+
+impl<T: EcsBundle> AbstractBundle for T {
+    fn component_types(&self) -> &'static [ComponentType] {
+        T::COMPONENT_TYPES
+    }
+
+    fn get_component(&self, component_type: ComponentType) -> Option<&dyn Any> {
+        EcsBundle::get_component(self, component_type)
+    }
+
+    fn add_to(self: Box<Self>, entity: InternalEntityKey, volatile: &mut VolatileWorld) {
+        EcsBundle::add_to(*self, &mut VolatileBundleTarget { entity, volatile });
+    }
+}
+
+pub(crate) struct VolatileBundleTarget<'a> {
+    pub(crate) entity: InternalEntityKey,
+    pub(crate) volatile: &'a mut VolatileWorld,
+}
+
+impl<'a> BundleTarget for VolatileBundleTarget<'a> {
+    fn add<T: EcsComponent>(&mut self, value: T) {
+        self.volatile.add_component_internal(
+            ComponentKey::new(self.entity, T::get_component_type()),
+            value,
+        );
+    }
+}
+
+pub(crate) struct SpawnBatch<T> {
+    pub(crate) entities: Vec<(TempEntityKey, T)>,
+}
+
+// persisted entities of a batch awaiting flush_component_addition, bundles are kept as they are
+// and moved into the stable pools at once
+pub(crate) struct PersistedBatch<T> {
+    pub(crate) entities: Vec<InternalEntityKey>,
+    pub(crate) bundles: Vec<T>,
+    pub(crate) cause: Cause,
+}
+
+pub(crate) trait AbstractSpawnBatch {
+    fn len(&self) -> usize;
+
+    fn apply(self: Box<Self>, volatile: &mut VolatileWorld, entity_storage: &mut EntityStorage);
+}
+
+pub(crate) trait AbstractPersistedBatch {
+    fn flush(self: Box<Self>, world: &mut World, result: &mut ExecutionResult);
+}
+
+impl<T: EcsBundle> AbstractSpawnBatch for SpawnBatch<T> {
+    fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    fn apply(self: Box<Self>, volatile: &mut VolatileWorld, entity_storage: &mut EntityStorage) {
+        let len = self.entities.len();
+        entity_storage.reserve(len);
+        volatile.entities_to_commit.reserve(len);
+        let mut entities = Vec::with_capacity(len);
+        let mut bundles = Vec::with_capacity(len);
+        for (entity, bundle) in self.entities {
+            entities.push(volatile.persist_entity(entity, entity_storage));
+            bundles.push(bundle);
+        }
+        let cause = volatile.current_cause.clone();
+        volatile.batches_to_add.push(Box::new(PersistedBatch {
+            entities,
+            bundles,
+            cause,
+        }));
+    }
+}
+
+impl<T: EcsBundle> AbstractPersistedBatch for PersistedBatch<T> {
+    fn flush(self: Box<Self>, world: &mut World, result: &mut ExecutionResult) {
+        world.flush_batch_addition(*self, result);
+    }
+}
+
+pub(crate) struct StableBundleTarget<'a> {
+    pub(crate) entity: InternalEntityKey,
+    pub(crate) stable: &'a mut StableWorld,
+}
+
+impl<'a> BundleTarget for StableBundleTarget<'a> {
+    fn add<T: EcsComponent>(&mut self, value: T) {
+        let data = self
+            .stable
+            .component_data
+            .get_pool_mut(T::get_component_type())
+            .specializable_mut()
+            .try_specialize::<T>()
+            .unwrap()
+            .add(value);
+        self.stable
+            .get_component_mapping_mut(T::get_component_type())
+            .insert(self.entity.index, data);
+    }
+}
@@ -1,18 +1,39 @@
 use std::borrow::Cow;
 use crate::ctx::Ctx;
+use crate::entity_key::EntityKey;
+use crate::internal::entity_storage::ValidateUncommitted::DenyUncommitted;
 use crate::internal::execution::invoke_user_code;
 use crate::internal::execution::ExecutionResult;
 use crate::internal::execution::UserCode;
 use crate::internal::world_pipeline::execute_all_internal;
 use crate::module::Module;
+use crate::run_condition::module_key;
+use crate::run_condition::ModuleKey;
+use crate::stage::Stages;
 use crate::ConfigurableWorld;
+use crate::world_result::WorldError;
+use crate::world_result::WorldResult;
 use crate::World;
 use log::trace;
+use to_vec::ToVec;
+use std::any::type_name;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::mem;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::panic::RefUnwindSafe;
 use std::panic::UnwindSafe;
 use std::sync::RwLock;
 
 pub struct EcsContainerBuilder {
     world: ConfigurableWorld,
+    auto_shrink: bool,
+    stages: Stages,
+    modules: HashMap<ModuleKey, AddedModule>,
+    // modules passed to `add_module`, others are added as dependencies only
+    explicit_modules: HashSet<ModuleKey>,
 }
 
 impl EcsContainerBuilder {
@@ -24,28 +45,127 @@ impl EcsContainerBuilder {
         self
     }
 
+    /// Adds the module, unless it's already added as a dependency of another one.
     pub fn add_module(mut self, module: &RwLock<Module>) -> EcsContainerBuilder {
-        for task in module.read().unwrap().tasks.iter() {
-            (task.action)(&mut self.world);
+        let key = module_key(module);
+        let name = module.read().unwrap().name;
+        assert!(
+            self.explicit_modules.insert(key),
+            "module {} is added twice",
+            name
+        );
+        if !self.modules.contains_key(&key) {
+            self.add_module_with_dependencies(module, &mut vec![]);
+        }
+        self
+    }
+
+    // `path` holds modules whose dependencies are being added
+    fn add_module_with_dependencies(
+        &mut self,
+        module: &RwLock<Module>,
+        path: &mut Vec<(ModuleKey, &'static str)>,
+    ) {
+        let key = module_key(module);
+        if let Some(start) = path.iter().position(|(it, _)| *it == key) {
+            let cycle = path[start..].iter().map(|(_, name)| *name).to_vec();
+            panic!(
+                "module dependency cycle: {} -> {}",
+                cycle.join(" -> "),
+                cycle[0]
+            );
+        }
+        let module = module.read().unwrap();
+        path.push((key, module.name));
+        for dependency in module.dependencies.iter() {
+            if !self.modules.contains_key(&module_key(dependency)) {
+                trace!("add module required by {}", module.name);
+                self.add_module_with_dependencies(dependency, path);
+            }
         }
+        path.pop();
+        trace!("add module {}", module.name);
+        let added = AddedModule {
+            name: module.name,
+            dependencies: module.dependencies.iter().map(|it| module_key(it)).to_vec(),
+        };
+        self.modules.insert(key, added);
+        apply_module(&mut self.world, key, &module);
+    }
+
+    /// Adds the module along with its config, handlers read it with `Ctx::module_config`.
+    pub fn add_module_with<C: RefUnwindSafe + 'static>(
+        mut self,
+        module: &RwLock<Module>,
+        config: C,
+    ) -> EcsContainerBuilder {
+        let previous = self
+            .world
+            .fetus
+            .stable
+            .module_configs
+            .insert((module_key(module), TypeId::of::<C>()), Box::new(config));
+        assert!(
+            previous.is_none(),
+            "module config {} is provided twice",
+            type_name::<C>()
+        );
+        self.add_module(module)
+    }
+
+    /// Makes the container shrink itself after execution once most of the entity slots are free.
+    pub fn auto_shrink(mut self) -> EcsContainerBuilder {
+        self.auto_shrink = true;
+        self
+    }
+
+    /// Changes the stages sent by `EcsContainer::tick`, e.g. inserts a custom one after `Update`.
+    pub fn stages(mut self, configure: impl FnOnce(&mut Stages)) -> EcsContainerBuilder {
+        configure(&mut self.stages);
         self
     }
 
     pub fn seal(self) -> EcsContainer {
         EcsContainer {
             world: self.world.fetus,
+            auto_shrink: self.auto_shrink,
+            stages: self.stages,
+            modules: self.modules,
         }
     }
 }
 
+// added module along with keys of the modules it depends on
+struct AddedModule {
+    name: &'static str,
+    dependencies: Vec<ModuleKey>,
+}
+
+fn apply_module(world: &mut ConfigurableWorld, key: ModuleKey, module: &Module) {
+    let gates = &mut world.fetus.immutable.gates;
+    gates.register_module(key, &module.conditions);
+    gates.configured_module = Some(key);
+    for task in module.tasks.iter() {
+        (task.action)(world);
+    }
+    world.fetus.immutable.gates.configured_module = None;
+}
+
 pub struct EcsContainer {
-    world: World,
+    pub(crate) world: World,
+    auto_shrink: bool,
+    pub(crate) stages: Stages,
+    modules: HashMap<ModuleKey, AddedModule>,
 }
 
 impl EcsContainer {
     pub fn create() -> EcsContainerBuilder {
         EcsContainerBuilder {
             world: ConfigurableWorld::new(),
+            auto_shrink: false,
+            stages: Stages::default(),
+            modules: HashMap::new(),
+            explicit_modules: HashSet::new(),
         }
     }
 
@@ -62,12 +182,137 @@ impl EcsContainer {
             stable,
             &mut self.world.entity_storage,
             name,
+            None,
             [],
             [UserCode::new(actions)],
             |r| return_value = Some(r),
             &(),
         );
         result += execute_all_internal(&mut self.world);
+        if self.auto_shrink && self.world.entity_storage.is_sparse() {
+            self.shrink_to_fit();
+        }
         (return_value, result)
     }
+
+    /// Releases memory left after destroyed entities (e.g. on level unload).
+    /// Only free slots after the last live entity are released, live keys stay valid.
+    pub fn shrink_to_fit(&mut self) {
+        trace!("shrink to fit");
+        self.world.shrink_to_fit();
+    }
+
+    /// Removes handlers and configs of the module, e.g. to unload a plugin.
+    /// Modules depending on it have to be removed first.
+    pub fn remove_module(&mut self, module: &RwLock<Module>) {
+        let key = module_key(module);
+        let module = module.read().unwrap();
+        assert!(
+            self.modules.contains_key(&key),
+            "module {} is not added",
+            module.name
+        );
+        if let Some(dependent) = self
+            .modules
+            .values()
+            .find(|it| it.dependencies.contains(&key))
+        {
+            panic!("module {} is required by {}", module.name, dependent.name);
+        }
+        trace!("remove module {}", module.name);
+        self.modules.remove(&key);
+        self.world.remove_module_registrations(key);
+        self.world
+            .stable
+            .module_configs
+            .retain(|(module, _), _| *module != key);
+        self.world.immutable.gates.remove_module(key);
+    }
+
+    /// Replaces handlers of the module with the ones registered by its current configurators,
+    /// e.g. after its code is reloaded. Entities are kept, new appear handlers are invoked only
+    /// for entities appeared after the replacement, while new aggregates and indexes include
+    /// existing entities. Switches of the module stay valid. If a configurator panics,
+    /// the module is left without registrations.
+    pub fn replace_module(&mut self, module: &RwLock<Module>) {
+        let key = module_key(module);
+        let module = module.read().unwrap();
+        assert!(
+            self.modules.contains_key(&key),
+            "module {} is not added",
+            module.name
+        );
+        trace!("replace module {}", module.name);
+        self.world.remove_module_registrations(key);
+        self.world.immutable.gates.reset_module(key);
+        let mut world = ConfigurableWorld {
+            fetus: mem::replace(&mut self.world, World::empty()),
+        };
+        let applied = panic::catch_unwind(AssertUnwindSafe(|| {
+            apply_module(&mut world, key, &module);
+        }));
+        self.world = world.fetus;
+        if let Err(panic) = applied {
+            self.world.immutable.gates.configured_module = None;
+            self.world.remove_module_registrations(key);
+            self.world.immutable.gates.reset_module(key);
+            panic::resume_unwind(panic);
+        }
+    }
+
+    pub(crate) fn has_signal_handlers<T: 'static>(&self) -> bool {
+        self.world
+            .volatile
+            .signal_storage
+            .payloads
+            .contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn entity_capacity(&self) -> usize {
+        self.world.entity_storage.capacity()
+    }
+
+    /// Copies entities from another container (e.g. level editor one) and returns keys of the copies.
+    /// If `remap_keys` is set, keys of the copied entities held by components implementing
+    /// `MapEntityKeys` are replaced by keys of the corresponding copies, other keys are left as is.
+    /// Errors of the copy transaction (e.g. of appear handlers) are returned along with the keys,
+    /// if it panics (e.g. in a mapper) nothing is copied and `WorldError::Aborted` is returned.
+    pub fn copy_entities_from(
+        &mut self,
+        source: &EcsContainer,
+        entities: &[EntityKey],
+        remap_keys: bool,
+    ) -> (WorldResult<Vec<EntityKey>>, ExecutionResult) {
+        let copies = entities
+            .iter()
+            .map(|entity| {
+                let entity = entity.validate(&source.world.entity_storage, DenyUncommitted)?;
+                source.world.stable.clone_components(entity.index)
+            })
+            .collect::<WorldResult<Vec<_>>>();
+        let copies = match copies {
+            Ok(copies) => copies,
+            Err(err) => return (Err(err), ExecutionResult::new()),
+        };
+        let mappers = &source.world.stable.entity_key_mappers;
+        let mut copies = AssertUnwindSafe(copies);
+        let (keys, result) = self.execute_once("copy_entities_from", move |ctx| {
+            let created = entities.iter().map(|_| ctx.create_entity()).to_vec();
+            let keys = created.iter().map(|it| it.key()).to_vec();
+            let map: HashMap<_, _> = entities.iter().copied().zip(keys.iter().copied()).collect();
+            for (entity, components) in created.into_iter().zip(copies.iter_mut()) {
+                let mut components = mem::take(components);
+                if remap_keys {
+                    for (component_type, value) in &mut components {
+                        if let Some(mapper) = mappers.get(component_type) {
+                            mapper(value.as_mut(), &|key| map.get(&key).copied().unwrap_or(key));
+                        }
+                    }
+                }
+                entity.add_values(components);
+            }
+            keys
+        });
+        (keys.ok_or(WorldError::Aborted), result)
+    }
 }
@@ -1,19 +1,37 @@
 use std::any::type_name;
+use std::any::Any;
+use std::any::TypeId;
+use crate::aggregate::EcsAggregate;
+use crate::bundle::EcsBundle;
+use crate::bundle::SpawnBatch;
 use crate::entity::Entity;
 use crate::entity_key::EntityKey;
 use crate::entity_uncommitted::UncommittedEntity;
 use crate::filter::FilterDesc;
+use crate::index::EcsIndexed;
 use crate::internal::change_buffer::Change;
 use crate::internal::change_buffer::ChangeBuffer;
 use crate::internal::entity_storage::EntityStorage;
 use crate::internal::entity_storage::ValidateUncommitted;
+use crate::run_condition::ModuleKey;
 use crate::world_result::EntityError;
+#[cfg(feature = "prefab")]
+use crate::prefab::PrefabOverrides;
+#[cfg(feature = "prefab")]
+use crate::world_result::PrefabError;
+use crate::world_result::QueryError;
+use crate::world_result::WorldResult;
+#[cfg(feature = "uuid")]
+use crate::stable_id::StableId;
 use crate::StableWorld;
 use std::cell::RefCell;
+use to_vec::ToVec;
 
 #[derive(Copy, Clone)]
 pub struct Ctx<'a, TSignal = ()> {
     pub signal: &'a TSignal,
+    // module of the invoked handler, its configs take precedence
+    module: Option<ModuleKey>,
     stable: &'a StableWorld,
     entity_storage: &'a EntityStorage,
     changes: &'a RefCell<&'a mut ChangeBuffer>,
@@ -22,12 +40,14 @@ pub struct Ctx<'a, TSignal = ()> {
 impl<'a, TSignal> Ctx<'a, TSignal> {
     pub(crate) fn new(
         signal: &'a TSignal,
+        module: Option<ModuleKey>,
         stable: &'a StableWorld,
         entity_storage: &'a EntityStorage,
         changes: &'a RefCell<&'a mut ChangeBuffer>,
     ) -> Ctx<'a, TSignal> {
         Ctx {
             signal,
+            module,
             stable,
             entity_storage,
             changes,
@@ -40,9 +60,6 @@ impl<'a, TSignal> Ctx<'a, TSignal> {
             .entity_storage
             .generate_temporary(&mut changes.entity_key_generator);
         let key = entity_key.inner;
-
-        self.entity_storage
-            .generate_temporary(&mut changes.entity_key_generator);
         changes.changes.push(Change::EntityCreate(entity_key));
         UncommittedEntity {
             key,
@@ -52,6 +69,58 @@ impl<'a, TSignal> Ctx<'a, TSignal> {
         }
     }
 
+    #[cfg(feature = "prefab")]
+    pub fn spawn_prefab(&self, name: &str) -> Result<UncommittedEntity<'a>, PrefabError> {
+        self.spawn_prefab_with(name, &PrefabOverrides::new())
+    }
+
+    #[cfg(feature = "prefab")]
+    pub fn spawn_prefab_with(
+        &self,
+        name: &str,
+        overrides: &PrefabOverrides,
+    ) -> Result<UncommittedEntity<'a>, PrefabError> {
+        let components = self.stable.prefabs.instantiate(name, overrides)?;
+        Ok(self.create_entity().add_values(components))
+    }
+
+    // all components of the source should be clonable (implement `Clone`)
+    pub fn clone_entity(&self, key: EntityKey) -> WorldResult<UncommittedEntity<'a>> {
+        let source = key.validate(self.entity_storage, ValidateUncommitted::DenyUncommitted)?;
+        let components = self.stable.clone_components(source.index)?;
+        Ok(self.create_entity().add_values(components))
+    }
+
+    // entities are committed together: their components are moved into the pools and matched
+    // against filters once per batch, appear events are still delivered for each entity
+    pub fn spawn_batch<T: EcsBundle>(&self, bundles: impl IntoIterator<Item = T>) -> Vec<EntityKey> {
+        let mut changes = self.changes.borrow_mut();
+        let entities = bundles
+            .into_iter()
+            .map(|bundle| {
+                let entity = self
+                    .entity_storage
+                    .generate_temporary(&mut changes.entity_key_generator);
+                (entity, bundle)
+            })
+            .to_vec();
+        let keys = entities.iter().map(|(it, _)| it.inner.export()).to_vec();
+        changes
+            .changes
+            .push(Change::SpawnBatch(Box::new(SpawnBatch { entities })));
+        keys
+    }
+
+    // destroys committed entities matched by the filter, returns their count. it's a shortcut
+    // for destroying them one by one, each goes through the usual disappear events
+    pub fn destroy_all(&self, filter: FilterDesc) -> usize {
+        let entities = self.stable.query_internal(filter).to_vec();
+        let count = entities.len();
+        let mut changes = self.changes.borrow_mut();
+        changes.changes.push(Change::EntityDestroyBatch(entities));
+        count
+    }
+
     pub fn get_entity(&self, key: EntityKey) -> Option<Entity<'a>> {
         let result = key.validate(self.entity_storage, ValidateUncommitted::DenyUncommitted);
         let entity_key = match result {
@@ -72,6 +141,12 @@ impl<'a, TSignal> Ctx<'a, TSignal> {
         })
     }
 
+    #[cfg(feature = "uuid")]
+    pub fn find_by_stable_id(&self, id: StableId) -> Option<Entity<'a>> {
+        let key = self.stable.stable_ids.get(id)?;
+        self.get_entity(key.export())
+    }
+
     pub fn send_signal<T: 'static>(&self, signal: T) {
         let mut changes = self.changes.borrow_mut();
         changes
@@ -84,9 +159,115 @@ impl<'a, TSignal> Ctx<'a, TSignal> {
             ));
     }
 
+    // only entity signal handlers of the target are invoked, if their filters match it
+    pub fn send_signal_to<T: 'static>(&self, entity: EntityKey, signal: T) {
+        let mut changes = self.changes.borrow_mut();
+        changes.changes.push(Change::SignalSend(
+            Box::new(move |volatile| {
+                volatile.signal_to(entity.inner, signal);
+            }),
+            type_name::<T>(),
+        ));
+    }
+
     pub fn query(&self, filter: FilterDesc) -> impl Iterator<Item=Entity<'a>> + '_ {
         self.stable
             .query(filter)
             .map(|it| self.get_entity(it).unwrap())
     }
+
+    pub fn query_count(&self, filter: FilterDesc) -> usize {
+        self.stable.query_count(filter)
+    }
+
+    pub fn query_is_empty(&self, filter: FilterDesc) -> bool {
+        self.stable.query_is_empty(filter)
+    }
+
+    pub fn query_first(&self, filter: FilterDesc) -> Option<Entity<'a>> {
+        self.stable
+            .query_first(filter)
+            .map(|it| self.get_entity(it).unwrap())
+    }
+
+    pub fn query_single(&self, filter: FilterDesc) -> Result<Entity<'a>, QueryError> {
+        self.stable
+            .query_single(filter)
+            .map(|it| self.get_entity(it).unwrap())
+    }
+
+    // entities of the component indexed by `World::register_index` or `#[ecs(index)]`
+    pub fn lookup<C: EcsIndexed>(
+        &self,
+        key: &C::IndexKey,
+    ) -> impl Iterator<Item = Entity<'a>> + '_ {
+        self.stable
+            .lookup::<C>(key)
+            .into_iter()
+            .map(|it| self.get_entity(it.export()).unwrap())
+    }
+
+    // committed entities matched by the filter within the radius, see `World::register_spatial_index`
+    #[cfg(feature = "spatial")]
+    pub fn query_radius<const N: usize>(
+        &self,
+        center: [f32; N],
+        radius: f32,
+        filter: FilterDesc,
+    ) -> impl Iterator<Item = Entity<'a>> + '_ {
+        self.stable
+            .query_radius(center, radius, filter)
+            .into_iter()
+            .map(|it| self.get_entity(it.export()).unwrap())
+    }
+
+    // committed entities matched by the filter within the box, bounds are inclusive
+    #[cfg(feature = "spatial")]
+    pub fn query_aabb<const N: usize>(
+        &self,
+        min: [f32; N],
+        max: [f32; N],
+        filter: FilterDesc,
+    ) -> impl Iterator<Item = Entity<'a>> + '_ {
+        self.stable
+            .query_aabb(min, max, filter)
+            .into_iter()
+            .map(|it| self.get_entity(it.export()).unwrap())
+    }
+
+    pub fn aggregate<A: EcsAggregate>(&self) -> &'a A {
+        self.stable
+            .aggregates
+            .get::<A>()
+            .unwrap_or_else(|| panic!("aggregate is not registered: {}", type_name::<A>()))
+    }
+
+    /// Config provided with `EcsContainerBuilder::add_module_with`: the one of the handler's
+    /// module, otherwise the only one of this type (e.g. provided for a dependency).
+    pub fn module_config<C: 'static>(&self) -> &'a C {
+        let configs = &self.stable.module_configs;
+        let config = self
+            .module
+            .and_then(|module| configs.get(&(module, TypeId::of::<C>())))
+            .or_else(|| {
+                let mut found = configs
+                    .iter()
+                    .filter(|((_, it), _)| *it == TypeId::of::<C>())
+                    .map(|(_, config)| config);
+                let config = found.next();
+                assert!(
+                    found.next().is_none(),
+                    "module config {} is provided for several modules",
+                    type_name::<C>()
+                );
+                config
+            });
+        config
+            .and_then(|it| (&**it as &dyn Any).downcast_ref::<C>())
+            .unwrap_or_else(|| panic!("module config is not provided: {}", type_name::<C>()))
+    }
+
+    pub fn query_contains(&self, filter: FilterDesc, entity: EntityKey) -> bool {
+        self.stable.query_contains(filter, entity)
+    }
 }
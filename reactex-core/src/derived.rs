@@ -0,0 +1,120 @@
+use crate::component::component_type_of;
+use crate::component::sort_component_types;
+use crate::component::ComponentType;
+use crate::component::EcsComponent;
+use crate::entity::Entity;
+use crate::filter::FilterDesc;
+use crate::run_condition::ModuleKey;
+use crate::World;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::panic::RefUnwindSafe;
+use std::rc::Rc;
+
+/// Computes component `D` from source components of an entity,
+/// implemented for `Fn(&A) -> D`, `Fn(&A, &B) -> D` and so on up to four sources.
+pub trait DeriveFn<Sources, D>: RefUnwindSafe + 'static {
+    /// Source component types, sorted the same way as `ecs_filter!` does.
+    const SOURCE_TYPES: &'static [ComponentType];
+
+    /// `None` if the entity misses any of the sources.
+    fn derive(&self, entity: &Entity) -> Option<D>;
+}
+
+macro_rules! impl_derive_fn {
+    ($($source:ident),+) => {
+        impl<F, D, $($source),+> DeriveFn<($($source,)+), D> for F
+        where
+            F: Fn($(&$source),+) -> D + RefUnwindSafe + 'static,
+            $($source: EcsComponent),+
+        {
+            const SOURCE_TYPES: &'static [ComponentType] =
+                &sort_component_types([$(component_type_of::<$source>()),+]);
+
+            fn derive(&self, entity: &Entity) -> Option<D> {
+                Some(self($(entity.get::<$source>()?),+))
+            }
+        }
+    };
+}
+
+impl_derive_fn!(A);
+impl_derive_fn!(A, B);
+impl_derive_fn!(A, B, C);
+impl_derive_fn!(A, B, C, E);
+
+// derivation cycles never settle, so a component can't be derived from itself,
+// even through other derived components
+fn is_derived_from(
+    derivations: &HashMap<ComponentType, Vec<(ComponentType, Option<ModuleKey>)>>,
+    sources: &[ComponentType],
+    derived: ComponentType,
+) -> bool {
+    let mut queue = sources.to_vec();
+    let mut visited = HashSet::new();
+    while let Some(source) = queue.pop() {
+        if source == derived {
+            return true;
+        }
+        if visited.insert(source) {
+            let sources = derivations.get(&source).into_iter().flatten();
+            queue.extend(sources.map(|it| it.0));
+        }
+    }
+    false
+}
+
+impl World {
+    pub(crate) fn add_derived_component<S, D: EcsComponent, F: DeriveFn<S, D>>(
+        &mut self,
+        name: &'static str,
+        derive: F,
+    ) {
+        let derive = Rc::new(derive);
+        let filter = FilterDesc::new(F::SOURCE_TYPES);
+        let source_types = filter.component_types;
+        let derived = D::get_component_type();
+        assert!(
+            !is_derived_from(&self.immutable.derivations, source_types, derived),
+            "derivation cycle: {} is derived from itself",
+            D::NAME
+        );
+        let module = self.immutable.gates.configured_module;
+        self.immutable
+            .derivations
+            .entry(derived)
+            .or_default()
+            .extend(source_types.iter().map(|it| (*it, module)));
+
+        let on_appear = derive.clone();
+        self.add_appear_handler(name, filter, false, move |ctx, entity| {
+            let entity = ctx.get_entity(entity).unwrap();
+            if let Some(value) = on_appear.derive(&entity) {
+                if entity.get::<D>().is_some() {
+                    entity.set(value);
+                } else {
+                    entity.add(value);
+                }
+            }
+        });
+        self.add_disappear_handler(name, filter, false, |ctx, entity| {
+            let entity = ctx.get_entity(entity).unwrap();
+            if entity.get::<D>().is_some() {
+                entity.remove::<D>();
+            }
+        });
+        // the value is recomputed only while it exists, appeared entities get it from
+        // the appear handler
+        self.add_modify_handler(name, source_types, false, move |ctx, entity| {
+            let Some(entity) = ctx.get_entity(entity) else {
+                return;
+            };
+            if entity.get::<D>().is_none() {
+                return;
+            }
+            if let Some(value) = derive.derive(&entity) {
+                entity.set(value);
+            }
+        });
+    }
+}
@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 
+use crate::bundle::EcsBundle;
 use crate::component::EcsComponent;
 use crate::entity_key::EntityKey;
 use crate::internal::change_buffer::Change;
@@ -7,6 +8,7 @@ use crate::internal::change_buffer::ChangeBuffer;
 use crate::internal::component_key::ComponentKey;
 use crate::internal::entity_storage::EntityStorage;
 use crate::internal::world_extras::InternalEntityKey;
+use crate::state::EcsState;
 use crate::StableWorld;
 
 #[derive(Copy, Clone)]
@@ -35,12 +37,34 @@ impl<'a> Entity<'a> {
         ));
     }
 
+    pub fn add_bundle<TBundle: EcsBundle>(&self, bundle: TBundle) {
+        let mut changes = self.changes.borrow_mut();
+        changes
+            .changes
+            .push(Change::BundleAdd(self.key, Box::new(bundle)));
+    }
+
     pub fn get<TComponent: EcsComponent>(&self) -> Option<&TComponent> {
         self.stable
             .get_component::<TComponent>(self.key.export(), self.entity_storage)
             .unwrap()
     }
 
+    pub fn has_pending<TComponent: EcsComponent>(&self) -> bool {
+        self.changes.borrow().has_pending_component(
+            ComponentKey::new(self.key, TComponent::get_component_type()),
+            self.get::<TComponent>().is_some(),
+        )
+    }
+
+    // unlike `get`, takes into account changes made by current handler and not committed yet
+    pub fn get_pending<TComponent: EcsComponent + Clone>(&self) -> Option<TComponent> {
+        self.changes.borrow_mut().preview_component(
+            ComponentKey::new(self.key, TComponent::get_component_type()),
+            self.get::<TComponent>(),
+        )
+    }
+
     pub fn remove<TComponent: EcsComponent>(&self) {
         let mut changes = self.changes.borrow_mut();
         changes
@@ -51,6 +75,43 @@ impl<'a> Entity<'a> {
             )));
     }
 
+    pub fn remove_bundle<TBundle: EcsBundle>(&self) {
+        let mut changes = self.changes.borrow_mut();
+        for component_type in TBundle::COMPONENT_TYPES {
+            changes
+                .changes
+                .push(Change::ComponentRemove(ComponentKey::new(
+                    self.key,
+                    *component_type,
+                )));
+        }
+    }
+
+    pub fn set<TComponent: EcsComponent>(&self, value: TComponent) {
+        let mut changes = self.changes.borrow_mut();
+        changes.set_component(
+            ComponentKey::new(self.key, TComponent::get_component_type()),
+            value,
+        );
+    }
+
+    /// Modification closure which may borrow from the handler. It's applied immediately to the
+    /// pending value (see `get_pending`), so the component should be `Clone`, and the result is
+    /// committed the same way as `set`. The first scoped modification of a component costs
+    /// O(pending changes of the handler), the following ones without other changes queued in
+    /// between reuse the pending value.
+    pub fn modify_scoped<TComponent: EcsComponent + Clone>(
+        &self,
+        change: impl FnOnce(&mut TComponent),
+    ) {
+        let component_key = ComponentKey::new(self.key, TComponent::get_component_type());
+        let last_set = self.changes.borrow_mut().take_last_set(component_key);
+        if let Some(mut value) = last_set.or_else(|| self.get_pending::<TComponent>()) {
+            change(&mut value);
+            self.set(value);
+        }
+    }
+
     pub fn modify<TComponent: EcsComponent>(&self, change: impl FnOnce(&mut TComponent) + 'static) {
         let mut changes = self.changes.borrow_mut();
         changes.changes.push(Change::ComponentModification(
@@ -61,4 +122,14 @@ impl<'a> Entity<'a> {
             }),
         ));
     }
+
+    // applied atomically after other changes: exit handlers of the current state are invoked,
+    // then the new state is committed and enter handlers are invoked. ignored without a state
+    pub fn transition<TState: EcsState>(&self, state: TState) {
+        let mut changes = self.changes.borrow_mut();
+        changes.changes.push(Change::StateTransition(
+            ComponentKey::new(self.key, TState::get_component_type()),
+            Box::new(|value| *value.downcast_mut::<TState>().unwrap() = state),
+        ));
+    }
 }
@@ -1,12 +1,17 @@
 use crate::internal::entity_storage::EntityStorage;
 use crate::internal::entity_storage::ValidateUncommitted;
+use crate::internal::world_extras::EntityGeneration;
+use crate::internal::world_extras::EntityIndex;
 use crate::internal::world_extras::InternalEntityKey;
 use crate::world_result::EntityError;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+// between the generation and the index
+const UNUSED_BITS: u64 = 0xFFFF_0000;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct EntityKey {
     pub(crate) inner: InternalEntityKey,
 }
@@ -24,6 +29,28 @@ impl Debug for EntityKey {
 }
 
 impl EntityKey {
+    /// Packs the key into `u64`: index in the high half, generation in the low one.
+    pub fn to_bits(self) -> u64 {
+        (self.inner.index.index as u64) << 32 | self.inner.generation.0 as u64
+    }
+
+    /// Reverse of `to_bits`. Fails with `NotExists` if the bits between the index and the
+    /// generation are set, otherwise the key isn't validated, so it may be stale or not exist.
+    pub fn from_bits(bits: u64) -> Result<EntityKey, EntityError> {
+        if bits & UNUSED_BITS != 0 {
+            return Err(EntityError::NotExists);
+        }
+        Ok(EntityKey {
+            inner: InternalEntityKey {
+                index: EntityIndex {
+                    index: (bits >> 32) as u32,
+                },
+                generation: EntityGeneration(bits as u16),
+                temp: false,
+            },
+        })
+    }
+
     pub(crate) fn validate(
         &self,
         entity_storage: &EntityStorage,
@@ -33,3 +60,26 @@ impl EntityKey {
         Ok(self.inner)
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for EntityKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.to_bits())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EntityKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u64::deserialize(deserializer)?;
+        EntityKey::from_bits(bits).map_err(|_| {
+            serde::de::Error::custom(format!("invalid entity key bits {bits:#x}"))
+        })
+    }
+}
+
+/// Implemented by components holding keys of other entities, so the keys can be remapped when
+/// entities are copied to another container (see `EcsContainer::copy_entities_from`).
+pub trait MapEntityKeys {
+    fn map_entity_keys(&mut self, map: &dyn Fn(EntityKey) -> EntityKey);
+}
@@ -28,4 +28,14 @@ impl<'a, TComponent: EcsComponent> Mut<'a, TComponent> {
     pub fn modify(&self, change: impl FnOnce(&mut TComponent) + 'static) {
         self.entity.modify(change);
     }
+
+    pub fn set(&self, value: TComponent) {
+        self.entity.set(value);
+    }
+}
+
+impl<'a, TComponent: EcsComponent + Clone> Mut<'a, TComponent> {
+    pub fn modify_scoped(&self, change: impl FnOnce(&mut TComponent)) {
+        self.entity.modify_scoped(change);
+    }
 }
@@ -1,11 +1,15 @@
 use std::cell::RefCell;
 use std::ops::Add;
 
+use crate::bundle::EcsBundle;
 use crate::component::EcsComponent;
 use crate::entity::Entity;
 use crate::entity_key::EntityKey;
+use crate::internal::change_buffer::Change;
 use crate::internal::change_buffer::ChangeBuffer;
+use crate::internal::component_key::ComponentKey;
 use crate::internal::entity_storage::EntityStorage;
+use crate::internal::world_extras::ComponentValue;
 use crate::internal::world_extras::InternalEntityKey;
 use crate::StableWorld;
 
@@ -42,6 +46,42 @@ impl<'a> UncommittedEntity<'a> {
         .add(value);
         self
     }
+
+    pub(crate) fn add_values(self, components: Vec<ComponentValue>) -> UncommittedEntity<'a> {
+        let mut changes = self.changes.borrow_mut();
+        for (component_type, value) in components {
+            changes.changes.push(Change::ComponentAdd(
+                ComponentKey::new(self.key, component_type),
+                value,
+            ));
+        }
+        self
+    }
+
+    pub fn add_bundle<TBundle: EcsBundle>(self, bundle: TBundle) -> UncommittedEntity<'a> {
+        Entity {
+            key: self.key,
+            stable: self.stable,
+            entity_storage: self.entity_storage,
+            changes: self.changes,
+        }
+        .add_bundle(bundle);
+        self
+    }
+
+    pub fn has_pending<TComponent: EcsComponent>(&self) -> bool {
+        self.changes.borrow().has_pending_component(
+            ComponentKey::new(self.key, TComponent::get_component_type()),
+            false,
+        )
+    }
+
+    pub fn get_pending<TComponent: EcsComponent + Clone>(&self) -> Option<TComponent> {
+        self.changes.borrow_mut().preview_component(
+            ComponentKey::new(self.key, TComponent::get_component_type()),
+            None,
+        )
+    }
 }
 
 impl<'a, TComponent: EcsComponent> Add<TComponent> for UncommittedEntity<'a> {
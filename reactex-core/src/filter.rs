@@ -1,4 +1,6 @@
+use crate::component::component_type_of;
 use crate::component::ComponentType;
+use crate::component::EcsComponent;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use to_vec::ToVec;
@@ -26,6 +28,10 @@ impl FilterDesc {
     pub const fn new(component_types: &'static [ComponentType]) -> FilterDesc {
         FilterDesc { component_types }
     }
+
+    pub(crate) const fn of<T: EcsComponent>() -> FilterDesc {
+        FilterDesc::new(const { &[component_type_of::<T>()] })
+    }
 }
 
 #[macro_export]
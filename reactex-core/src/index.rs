@@ -0,0 +1,71 @@
+use crate::component::EcsComponent;
+use crate::internal::component_indexes::ComponentIndex;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::StableWorld;
+use crate::World;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::mem;
+use std::panic::RefUnwindSafe;
+use to_vec::ToVec;
+
+/// Component which entities are looked up by a key, implemented by `#[ecs(index)]`.
+/// Components indexed with `World::register_index` implement it by hand.
+pub trait EcsIndexed: EcsComponent {
+    type IndexKey: Clone + Hash + Eq + Debug + RefUnwindSafe + 'static;
+}
+
+impl World {
+    /// Indexes entities by a key extracted from their component `C` (see `#[ecs(index)]`),
+    /// they are looked up with `Ctx::lookup`.
+    pub fn register_index<C: EcsIndexed>(
+        &mut self,
+        extract: impl Fn(&C) -> C::IndexKey + RefUnwindSafe + 'static,
+    ) {
+        self.add_index(false, extract);
+    }
+
+    /// Same as `register_index`, but entities sharing a key are reported as execution errors
+    /// (see `#[ecs(index(unique))]`). Such entities are still indexed.
+    pub fn register_unique_index<C: EcsIndexed>(
+        &mut self,
+        extract: impl Fn(&C) -> C::IndexKey + RefUnwindSafe + 'static,
+    ) {
+        self.add_index(true, extract);
+    }
+
+    fn add_index<C: EcsIndexed>(
+        &mut self,
+        unique: bool,
+        extract: impl Fn(&C) -> C::IndexKey + RefUnwindSafe + 'static,
+    ) {
+        let component_type = C::get_component_type();
+        self.stable.indexes.add(
+            component_type,
+            Box::new(ComponentIndex {
+                unique,
+                extract: Box::new(extract),
+                entities: Default::default(),
+                keys: Default::default(),
+            }),
+            self.immutable.gates.configured_module,
+        );
+        // existing entities sharing a unique key aren't reported, they are still indexed
+        let mut indexes = mem::take(&mut self.stable.indexes);
+        for entity in self.existing_entities(&[component_type]) {
+            indexes.index(ComponentKey::new(entity, component_type), &self.stable);
+        }
+        self.stable.indexes = indexes;
+    }
+}
+
+impl StableWorld {
+    pub(crate) fn lookup<C: EcsIndexed>(&self, key: &C::IndexKey) -> Vec<InternalEntityKey> {
+        self.indexes
+            .get::<C, C::IndexKey>()
+            .unwrap_or_else(|| panic!("component is not indexed: {}", C::NAME))
+            .get(key)
+            .to_vec()
+    }
+}
@@ -0,0 +1,200 @@
+use crate::aggregate::EcsAggregate;
+use crate::component::ComponentType;
+use crate::component::EcsComponent;
+use crate::internal::filter_manager::InternalFilterKey;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::run_condition::ModuleKey;
+use crate::StableWorld;
+use std::any::Any;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::panic::RefUnwindSafe;
+
+pub(crate) struct Aggregate<A: EcsAggregate, C> {
+    pub(crate) value: A,
+    // contribution of every entity matched by the filter, to revert it on disappear
+    pub(crate) items: HashMap<InternalEntityKey, A::Item>,
+    pub(crate) extract: ItemExtractor<C, A::Item>,
+}
+
+pub(crate) type ItemExtractor<C, T> = Box<dyn Fn(&C) -> T + RefUnwindSafe>;
+
+pub(crate) trait AbstractAggregate: RefUnwindSafe {
+    fn value(&self) -> &dyn Any;
+
+    fn account(&mut self, entity: InternalEntityKey, stable: &StableWorld);
+
+    fn discount(&mut self, entity: InternalEntityKey);
+
+    fn refresh(&mut self, entity: InternalEntityKey, stable: &StableWorld);
+
+    fn shrink_to_fit(&mut self);
+}
+
+impl<A: EcsAggregate, C: EcsComponent> Aggregate<A, C> {
+    fn revert(&mut self, item: &A::Item) {
+        if self.value.remove(item) {
+            return;
+        }
+        self.value = A::default();
+        for item in self.items.values() {
+            self.value.add(item);
+        }
+    }
+}
+
+impl<A: EcsAggregate, C: EcsComponent> AbstractAggregate for Aggregate<A, C> {
+    fn value(&self) -> &dyn Any {
+        &self.value
+    }
+
+    fn account(&mut self, entity: InternalEntityKey, stable: &StableWorld) {
+        self.discount(entity);
+        let Some(component) = stable.get_component_no_validation::<C>(entity.index) else {
+            return;
+        };
+        let item = (self.extract)(component);
+        self.value.add(&item);
+        self.items.insert(entity, item);
+    }
+
+    fn discount(&mut self, entity: InternalEntityKey) {
+        if let Some(item) = self.items.remove(&entity) {
+            self.revert(&item);
+        }
+    }
+
+    fn refresh(&mut self, entity: InternalEntityKey, stable: &StableWorld) {
+        if self.items.contains_key(&entity) {
+            self.account(entity, stable);
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.items.shrink_to_fit();
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct AggregateStorage {
+    aggregates: HashMap<TypeId, Box<dyn AbstractAggregate>>,
+    by_filter: HashMap<InternalFilterKey, Vec<TypeId>>,
+    by_component_type: HashMap<ComponentType, Vec<TypeId>>,
+    by_module: HashMap<ModuleKey, Vec<TypeId>>,
+}
+
+impl AggregateStorage {
+    pub(crate) fn add<A: EcsAggregate>(
+        &mut self,
+        filter: InternalFilterKey,
+        component_type: ComponentType,
+        aggregate: Box<dyn AbstractAggregate>,
+        module: Option<ModuleKey>,
+    ) {
+        let previous = self.aggregates.insert(TypeId::of::<A>(), aggregate);
+        assert!(
+            previous.is_none(),
+            "aggregate is registered twice: {}",
+            std::any::type_name::<A>()
+        );
+        self.by_filter
+            .entry(filter)
+            .or_default()
+            .push(TypeId::of::<A>());
+        self.by_component_type
+            .entry(component_type)
+            .or_default()
+            .push(TypeId::of::<A>());
+        if let Some(module) = module {
+            self.by_module
+                .entry(module)
+                .or_default()
+                .push(TypeId::of::<A>());
+        }
+    }
+
+    // returns filters of the removed aggregates, one per aggregate
+    pub(crate) fn remove_module(&mut self, module: ModuleKey) -> Vec<InternalFilterKey> {
+        let removed = self.by_module.remove(&module).unwrap_or_default();
+        for aggregate in &removed {
+            self.aggregates.remove(aggregate);
+        }
+        let mut filters = vec![];
+        for (filter, aggregates) in self.by_filter.iter_mut() {
+            aggregates.retain(|it| {
+                let kept = !removed.contains(it);
+                if !kept {
+                    filters.push(*filter);
+                }
+                kept
+            });
+        }
+        self.by_filter.retain(|_, it| !it.is_empty());
+        for aggregates in self.by_component_type.values_mut() {
+            aggregates.retain(|it| !removed.contains(it));
+        }
+        self.by_component_type.retain(|_, it| !it.is_empty());
+        filters
+    }
+
+    pub(crate) fn get<A: EcsAggregate>(&self) -> Option<&A> {
+        self.aggregates
+            .get(&TypeId::of::<A>())
+            .map(|it| it.value().downcast_ref().unwrap())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.aggregates.is_empty()
+    }
+
+    pub(crate) fn is_aggregated(&self, filter: InternalFilterKey) -> bool {
+        self.by_filter.contains_key(&filter)
+    }
+
+    pub(crate) fn on_appear<'a>(
+        &mut self,
+        filter: InternalFilterKey,
+        entities: impl Iterator<Item = &'a InternalEntityKey> + Clone,
+        stable: &StableWorld,
+    ) {
+        for aggregate in self.by_filter.get(&filter).into_iter().flatten() {
+            let aggregate = self.aggregates.get_mut(aggregate).unwrap();
+            for entity in entities.clone() {
+                aggregate.account(*entity, stable);
+            }
+        }
+    }
+
+    pub(crate) fn on_disappear<'a>(
+        &mut self,
+        filter: InternalFilterKey,
+        entities: impl Iterator<Item = &'a InternalEntityKey> + Clone,
+    ) {
+        for aggregate in self.by_filter.get(&filter).into_iter().flatten() {
+            let aggregate = self.aggregates.get_mut(aggregate).unwrap();
+            for entity in entities.clone() {
+                aggregate.discount(*entity);
+            }
+        }
+    }
+
+    pub(crate) fn on_modified(
+        &mut self,
+        component_type: ComponentType,
+        entity: InternalEntityKey,
+        stable: &StableWorld,
+    ) {
+        for aggregate in self.by_component_type.get(&component_type).into_iter().flatten() {
+            self.aggregates
+                .get_mut(aggregate)
+                .unwrap()
+                .refresh(entity, stable);
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for aggregate in self.aggregates.values_mut() {
+            aggregate.shrink_to_fit();
+        }
+    }
+}
@@ -1,9 +1,15 @@
 use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
+use std::mem;
 use log::trace;
 
+use crate::bundle::AbstractBundle;
+use crate::bundle::AbstractSpawnBatch;
+use crate::component::EcsComponent;
 use crate::internal::component_key::ComponentKey;
 use crate::internal::component_mappings::ComponentMappingStorage;
+use crate::internal::component_pool_manager::ComponentPoolManager;
+use crate::internal::component_pool_manager::TempComponentDataKey;
 use crate::internal::entity_key_generator::TemporaryEntityKeyStorage;
 use crate::internal::entity_storage::EntityStorage;
 use crate::internal::world_extras::InternalEntityKey;
@@ -13,13 +19,18 @@ use crate::VolatileWorld;
 pub(crate) enum Change {
     EntityCreate(TempEntityKey),
     EntityDestroy(InternalEntityKey),
+    EntityDestroyBatch(Vec<InternalEntityKey>),
+    SpawnBatch(Box<dyn AbstractSpawnBatch>),
     ComponentAdd(ComponentKey, Box<dyn Any>),
+    BundleAdd(InternalEntityKey, Box<dyn AbstractBundle>),
     ComponentRemove(ComponentKey),
     ComponentModification(ComponentKey, ComponentModification),
+    ComponentSet(ComponentKey, TempComponentDataKey),
+    StateTransition(ComponentKey, ComponentModification),
     SignalSend(Box<dyn FnOnce(&mut VolatileWorld)>, &'static str),
 }
 
-type ComponentModification = Box<dyn FnOnce(&mut dyn Any)>;
+pub(crate) type ComponentModification = Box<dyn FnOnce(&mut dyn Any)>;
 
 pub(crate) struct TempEntityKey {
     pub(crate) inner: InternalEntityKey,
@@ -40,23 +51,186 @@ impl Debug for TempEntityKey {
 pub(crate) struct ChangeBuffer {
     pub(crate) changes: Vec<Change>,
     pub(crate) entity_key_generator: TemporaryEntityKeyStorage,
+    // values passed to `set`. they live here until flush_component_modification
+    pub(crate) component_data: ComponentPoolManager<TempComponentDataKey>,
+}
+
+impl Default for ChangeBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ChangeBuffer {
-    pub(crate) fn new(entity_key_generator: TemporaryEntityKeyStorage) -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             changes: vec![],
-            entity_key_generator,
+            entity_key_generator: TemporaryEntityKeyStorage::new(),
+            component_data: Default::default(),
+        }
+    }
+
+    pub(crate) fn set_component<T: EcsComponent>(
+        &mut self,
+        component_key: ComponentKey,
+        value: T,
+    ) {
+        let data = self
+            .component_data
+            .get_pool_mut(T::get_component_type())
+            .specializable_mut()
+            .try_specialize::<T>()
+            .unwrap()
+            .add(value);
+        self.changes.push(Change::ComponentSet(component_key, data));
+    }
+
+    // dequeues the last queued change if it's a `set` of the component, so a change based
+    // on its value can be queued instead
+    pub(crate) fn take_last_set<T: EcsComponent>(
+        &mut self,
+        component_key: ComponentKey,
+    ) -> Option<T> {
+        let Some(Change::ComponentSet(key, _)) = self.changes.last() else {
+            return None;
+        };
+        if *key != component_key {
+            return None;
+        }
+        let Some(Change::ComponentSet(_, data)) = self.changes.pop() else {
+            unreachable!()
+        };
+        self.component_data
+            .get_pool_mut(component_key.component_type)
+            .specializable_mut()
+            .try_specialize::<T>()
+            .unwrap()
+            .del_and_get(&data)
+    }
+
+    pub(crate) fn has_pending_component(
+        &self,
+        component_key: ComponentKey,
+        committed: bool,
+    ) -> bool {
+        let mut committed = committed;
+        let mut added = false;
+        for change in &self.changes {
+            match change {
+                Change::EntityDestroy(entity) if *entity == component_key.entity => return false,
+                Change::EntityDestroyBatch(entities) if entities.contains(&component_key.entity) => {
+                    return false
+                }
+                Change::ComponentAdd(key, _) if *key == component_key => added = true,
+                Change::BundleAdd(entity, bundle)
+                    if *entity == component_key.entity
+                        && bundle.component_types().contains(&component_key.component_type) =>
+                {
+                    added = true
+                }
+                Change::ComponentRemove(key) if *key == component_key => {
+                    // removal cancels uncommitted addition first (see remove_component_internal)
+                    if added {
+                        added = false;
+                    } else {
+                        committed = false;
+                    }
+                }
+                _ => {}
+            }
+        }
+        committed || added
+    }
+
+    // read-only for the queue shape: modification closures can be run only once, so every
+    // pending modification of the component is run on the preview and replaced in place with
+    // an overwrite by its result. the order and number of changes stay the same
+    pub(crate) fn preview_component<T: EcsComponent + Clone>(
+        &mut self,
+        component_key: ComponentKey,
+        committed: Option<&T>,
+    ) -> Option<T> {
+        let mut committed = committed.cloned();
+        let mut added: Option<T> = None;
+        let mut modified = false;
+        for change in &self.changes {
+            match change {
+                Change::EntityDestroy(entity) if *entity == component_key.entity => return None,
+                Change::EntityDestroyBatch(entities) if entities.contains(&component_key.entity) => {
+                    return None
+                }
+                Change::ComponentAdd(key, value) if *key == component_key => {
+                    // the first addition wins (see flush_component_addition)
+                    if added.is_none() {
+                        added = value.downcast_ref::<T>().cloned();
+                    }
+                }
+                Change::BundleAdd(entity, bundle) if *entity == component_key.entity => {
+                    if added.is_none() {
+                        added = bundle
+                            .get_component(component_key.component_type)
+                            .and_then(|it| it.downcast_ref::<T>())
+                            .cloned();
+                    }
+                }
+                Change::ComponentRemove(key) if *key == component_key => {
+                    if added.is_some() {
+                        added = None;
+                    } else {
+                        committed = None;
+                    }
+                }
+                Change::ComponentModification(key, _) | Change::ComponentSet(key, _)
+                    if *key == component_key =>
+                {
+                    modified = true
+                }
+                _ => {}
+            }
         }
+        let mut value = added.or(committed)?;
+        if !modified {
+            return Some(value);
+        }
+
+        // modifications are flushed after additions and removals, so only their order
+        // relative to each other matters
+        for change in self.changes.iter_mut() {
+            match change {
+                Change::ComponentModification(key, modification) if *key == component_key => {
+                    let modification = mem::replace(modification, Box::new(|_| {}));
+                    modification(&mut value);
+                    let result = value.clone();
+                    *change = Change::ComponentModification(
+                        component_key,
+                        Box::new(move |state| *state.downcast_mut::<T>().unwrap() = result),
+                    );
+                }
+                Change::ComponentSet(key, data) if *key == component_key => {
+                    value = self
+                        .component_data
+                        .get_pool(key.component_type)
+                        .unwrap()
+                        .specializable()
+                        .try_specialize::<T>()
+                        .unwrap()
+                        .get(data)
+                        .unwrap()
+                        .clone();
+                }
+                _ => {}
+            }
+        }
+        Some(value)
     }
 
     pub(crate) fn apply_to(
-        self,
+        &mut self,
         volatile: &mut VolatileWorld,
         entity_storage: &mut EntityStorage,
         component_mappings: &ComponentMappingStorage,
     ) {
-        for change in self.changes {
+        for change in self.changes.drain(..) {
             match change {
                 Change::EntityCreate(entity) => {
                     trace!("request create entity {}", entity);
@@ -66,10 +240,24 @@ impl ChangeBuffer {
                     trace!("request destroy entity {}", entity);
                     volatile.destroy_entity_internal(entity, entity_storage);
                 }
+                Change::EntityDestroyBatch(entities) => {
+                    trace!("request destroy {} entities", entities.len());
+                    for entity in entities {
+                        volatile.destroy_entity_internal(entity, entity_storage);
+                    }
+                }
+                Change::SpawnBatch(batch) => {
+                    trace!("request spawn {} entities", batch.len());
+                    batch.apply(volatile, entity_storage);
+                }
                 Change::ComponentAdd(component_key, value) => {
                     trace!("request add component {}", component_key);
                     volatile.add_component_dyn_internal(component_key, value);
                 }
+                Change::BundleAdd(entity, bundle) => {
+                    trace!("request add bundle {}", entity);
+                    bundle.add_to(entity, volatile);
+                }
                 Change::ComponentRemove(component_key) => {
                     trace!("request remove component {}", component_key);
                     volatile
@@ -80,6 +268,14 @@ impl ChangeBuffer {
                     trace!("request modify component {}", component_key);
                     volatile.modify_component_internal(component_key, modification);
                 }
+                Change::ComponentSet(component_key, data) => {
+                    trace!("request set component {}", component_key);
+                    volatile.set_component_internal(component_key, data);
+                }
+                Change::StateTransition(component_key, assign) => {
+                    trace!("request state transition {}", component_key);
+                    volatile.transition_state_internal(component_key, assign);
+                }
                 Change::SignalSend(signal, type_name) => {
                     trace!("request signal send {}", type_name);
                     signal(volatile);
@@ -0,0 +1,157 @@
+use crate::component::ComponentType;
+use crate::component::EcsComponent;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::run_condition::ModuleKey;
+use crate::StableWorld;
+use std::any::Any;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::panic::RefUnwindSafe;
+
+pub(crate) struct ComponentIndex<C, K> {
+    pub(crate) unique: bool,
+    pub(crate) extract: KeyExtractor<C, K>,
+    pub(crate) entities: HashMap<K, HashSet<InternalEntityKey>>,
+    // key of every indexed entity, so the entity is unindexed without the component value
+    pub(crate) keys: HashMap<InternalEntityKey, K>,
+}
+
+pub(crate) type KeyExtractor<C, K> = Box<dyn Fn(&C) -> K + RefUnwindSafe>;
+
+pub(crate) trait AbstractIndex: RefUnwindSafe {
+    fn as_any(&self) -> &dyn Any;
+
+    // returns a description of the unique constraint violation, the entity is indexed anyway
+    fn index(&mut self, entity: InternalEntityKey, stable: &StableWorld) -> Option<String>;
+
+    fn unindex(&mut self, entity: InternalEntityKey);
+
+    fn shrink_to_fit(&mut self);
+}
+
+impl<C, K> ComponentIndex<C, K> {
+    pub(crate) fn get(&self, key: &K) -> impl Iterator<Item = InternalEntityKey> + '_
+    where
+        K: Hash + Eq,
+    {
+        self.entities.get(key).into_iter().flatten().copied()
+    }
+}
+
+impl<C, K> AbstractIndex for ComponentIndex<C, K>
+where
+    C: EcsComponent,
+    K: Clone + Hash + Eq + Debug + RefUnwindSafe + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn index(&mut self, entity: InternalEntityKey, stable: &StableWorld) -> Option<String> {
+        self.unindex(entity);
+        let component = stable.get_component_no_validation::<C>(entity.index)?;
+        let key = (self.extract)(component);
+        let entities = self.entities.entry(key.clone()).or_default();
+        let violation = match entities.iter().next() {
+            Some(other) if self.unique => Some(format!(
+                "unique index of {} violated: entities {} and {} have the same key {:?}",
+                C::NAME,
+                other,
+                entity,
+                key
+            )),
+            _ => None,
+        };
+        entities.insert(entity);
+        self.keys.insert(entity, key);
+        violation
+    }
+
+    fn unindex(&mut self, entity: InternalEntityKey) {
+        let Some(key) = self.keys.remove(&entity) else {
+            return;
+        };
+        if let Some(entities) = self.entities.get_mut(&key) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.entities.remove(&key);
+            }
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entities.shrink_to_fit();
+        self.keys.shrink_to_fit();
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct IndexStorage {
+    by_component_type: HashMap<ComponentType, Box<dyn AbstractIndex>>,
+    by_module: HashMap<ModuleKey, Vec<ComponentType>>,
+}
+
+impl IndexStorage {
+    pub(crate) fn add(
+        &mut self,
+        component_type: ComponentType,
+        index: Box<dyn AbstractIndex>,
+        module: Option<ModuleKey>,
+    ) {
+        let previous = self.by_component_type.insert(component_type, index);
+        assert!(
+            previous.is_none(),
+            "component is indexed twice: {}",
+            component_type
+        );
+        if let Some(module) = module {
+            self.by_module
+                .entry(module)
+                .or_default()
+                .push(component_type);
+        }
+    }
+
+    pub(crate) fn remove_module(&mut self, module: ModuleKey) {
+        for component_type in self.by_module.remove(&module).into_iter().flatten() {
+            self.by_component_type.remove(&component_type);
+        }
+    }
+
+    pub(crate) fn get<C: EcsComponent, K: 'static>(&self) -> Option<&ComponentIndex<C, K>> {
+        let index = self.by_component_type.get(&C::get_component_type())?;
+        let index = index.as_any().downcast_ref().unwrap_or_else(|| {
+            panic!(
+                "index of {} has key type other than {}",
+                C::NAME,
+                std::any::type_name::<K>()
+            )
+        });
+        Some(index)
+    }
+
+    pub(crate) fn index(
+        &mut self,
+        component_key: ComponentKey,
+        stable: &StableWorld,
+    ) -> Option<String> {
+        self.by_component_type
+            .get_mut(&component_key.component_type)?
+            .index(component_key.entity, stable)
+    }
+
+    pub(crate) fn unindex(&mut self, component_key: ComponentKey) {
+        if let Some(index) = self.by_component_type.get_mut(&component_key.component_type) {
+            index.unindex(component_key.entity);
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for index in self.by_component_type.values_mut() {
+            index.shrink_to_fit();
+        }
+    }
+}
@@ -20,4 +20,10 @@ impl ComponentMappingStorage {
             .map(|it| it.contains_key(&entity))
             .unwrap_or(false)
     }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for data_by_entity in self.data_by_entity_by_type.values_mut() {
+            data_by_entity.shrink_to_fit();
+        }
+    }
 }
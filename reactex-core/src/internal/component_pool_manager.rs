@@ -55,6 +55,12 @@ impl<TComponentDataKey: PoolKey> ComponentPoolManager<TComponentDataKey> {
         }
     }
 
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for pool in self.by_type.values_mut() {
+            pool.shrink_to_fit();
+        }
+    }
+
     pub(crate) fn get_pool(
         &self,
         component_type: ComponentType,
@@ -71,6 +77,8 @@ pub(crate) struct ComponentDataKey {
 }
 
 impl PoolKey for ComponentDataKey {
+    const SHARES_TAGS: bool = true;
+
     fn as_usize(&self) -> usize {
         self.index
     }
@@ -85,6 +93,8 @@ pub(crate) struct TempComponentDataKey {
 }
 
 impl PoolKey for TempComponentDataKey {
+    const SHARES_TAGS: bool = true;
+
     fn as_usize(&self) -> usize {
         self.index
     }
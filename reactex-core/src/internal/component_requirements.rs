@@ -0,0 +1,43 @@
+use crate::component::ComponentType;
+use std::any::Any;
+use std::collections::HashMap;
+
+pub(crate) struct RequiredComponent {
+    pub(crate) component_type: ComponentType,
+    pub(crate) constructor: fn() -> Box<dyn Any>,
+}
+
+// declared with #[ecs(requires(...))]
+#[derive(Default)]
+pub(crate) struct ComponentRequirements {
+    required: HashMap<ComponentType, Vec<RequiredComponent>>,
+    dependents: HashMap<ComponentType, Vec<ComponentType>>,
+}
+
+impl ComponentRequirements {
+    pub(crate) fn add(&mut self, dependent: ComponentType, required: RequiredComponent) {
+        self.dependents
+            .entry(required.component_type)
+            .or_default()
+            .push(dependent);
+        self.required.entry(dependent).or_default().push(required);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.required.is_empty()
+    }
+
+    pub(crate) fn get_required(&self, dependent: ComponentType) -> &[RequiredComponent] {
+        self.required
+            .get(&dependent)
+            .map(|it| it.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_dependents(&self, required: ComponentType) -> &[ComponentType] {
+        self.dependents
+            .get(&required)
+            .map(|it| it.as_slice())
+            .unwrap_or_default()
+    }
+}
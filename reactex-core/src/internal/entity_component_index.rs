@@ -97,6 +97,15 @@ impl EntityComponentIndex {
         copied.into_iter()
     }
 
+    // rows of entities beyond the capacity are dropped
+    pub(crate) fn shrink(&mut self, capacity: usize) {
+        if capacity < self.component_count.len() {
+            self.component_count = self.component_count[..capacity].into();
+            self.component_types =
+                self.component_types[..capacity * self.component_types_width].into();
+        }
+    }
+
     pub(crate) fn add_entity(&mut self, entity: EntityIndex) {
         let entity = entity.index as usize;
         if entity >= self.component_count.len() {
@@ -16,6 +16,10 @@ pub(crate) struct EntityStorage {
     entities: Box<[EntityBox]>,
     allocation_boundary: usize,
     holes: Vec<usize>,
+    min_capacity: usize,
+    // the highest generation of released slots, new slots start above it
+    // so stale keys pointing to them aren't made valid again
+    generation_floor: EntityGeneration,
 }
 
 impl EntityStorage {
@@ -48,7 +52,7 @@ impl EntityStorage {
             .entities
             .get(index)
             .map(|it| it.generation)
-            .unwrap_or_else(EntityGeneration::new);
+            .unwrap_or(self.generation_floor);
 
         let key = InternalEntityKey {
             index: EntityIndex {
@@ -69,7 +73,7 @@ impl EntityStorage {
             .entities
             .get_mut(input.inner.index.index as usize)
             .map(|it| it.generation)
-            .unwrap_or(EntityGeneration::new());
+            .unwrap_or(self.generation_floor);
         assert_eq!(generation.to_next_generation(), input.inner.generation);
 
         if self.holes.last().copied() == Some(index.index as usize) {
@@ -97,9 +101,12 @@ impl EntityStorage {
 impl EntityStorage {
     pub(crate) fn with_capacity(initial_capacity: usize) -> EntityStorage {
         EntityStorage {
-            entities: vec![EntityBox::new(); initial_capacity].into_boxed_slice(),
+            entities: vec![EntityBox::new(EntityGeneration::new()); initial_capacity]
+                .into_boxed_slice(),
             allocation_boundary: 0,
             holes: Default::default(),
+            min_capacity: initial_capacity,
+            generation_floor: EntityGeneration::new(),
         }
     }
 }
@@ -112,10 +119,10 @@ struct EntityBox {
 }
 
 impl EntityBox {
-    fn new() -> Self {
+    fn new(generation: EntityGeneration) -> Self {
         EntityBox {
             exists: false,
-            generation: EntityGeneration::new(),
+            generation,
             committed: false,
         }
     }
@@ -134,10 +141,10 @@ impl EntityStorage {
         entity: InternalEntityKey,
         uncommitted_strategy: ValidateUncommitted,
     ) -> Result<(), EntityError> {
-        let found = self
-            .entities
-            .get(entity.index.index as usize)
-            .expect("entity out of bounds");
+        // slots past the capacity are released by shrink_to_fit, stale keys may still point there
+        let Some(found) = self.entities.get(entity.index.index as usize) else {
+            return Err(NotExists);
+        };
         if !found.exists {
             return Err(NotExists);
         }
@@ -159,9 +166,24 @@ impl EntityStorage {
         key
     }
 
+    // makes room for entities persisted at once, so the storage isn't extended one by one
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        let required = self.allocation_boundary + additional;
+        let mut new_size = self.entities.len().max(1);
+        while new_size < required {
+            new_size *= 2;
+        }
+        if new_size > self.entities.len() {
+            self.resize(new_size);
+        }
+    }
+
     fn extend(&mut self) {
-        let new_size = self.entities.len() * 2;
-        let entity_box_template = EntityBox::new();
+        self.resize(self.entities.len() * 2);
+    }
+
+    fn resize(&mut self, new_size: usize) {
+        let entity_box_template = EntityBox::new(self.generation_floor);
         let prev = mem::replace(
             &mut self.entities,
             vec![entity_box_template; new_size].into_boxed_slice(),
@@ -169,18 +191,58 @@ impl EntityStorage {
         self.entities[0..prev.len()].copy_from_slice(&prev);
     }
 
+    pub(crate) fn capacity(&self) -> usize {
+        self.entities.len()
+    }
+
+    // most of the allocated slots are free, so shrinking is likely to reclaim memory
+    pub(crate) fn is_sparse(&self) -> bool {
+        self.entities.len() > self.min_capacity && self.holes.len() * 2 > self.allocation_boundary
+    }
+
+    // releases free slots after the last existing entity, returns the new capacity.
+    // Retired slots are kept, live keys are never affected
+    pub(crate) fn shrink_to_fit(&mut self) -> usize {
+        let mut boundary = self.allocation_boundary;
+        while boundary > 0 {
+            let entity = &self.entities[boundary - 1];
+            if entity.exists || entity.generation.is_last() {
+                break;
+            }
+            boundary -= 1;
+        }
+        trace!("shrinking entity storage to {} slots", boundary);
+        self.holes.retain(|it| *it < boundary);
+        self.holes.shrink_to_fit();
+        self.allocation_boundary = boundary;
+
+        let capacity = boundary.next_power_of_two().max(self.min_capacity);
+        if capacity < self.entities.len() {
+            let released = self.entities[capacity..].iter().map(|it| it.generation);
+            self.generation_floor = released.fold(self.generation_floor, Ord::max);
+            self.entities = self.entities[..capacity].into();
+        }
+        self.entities.len()
+    }
+
+    // released slots hold no committed entity
     pub(crate) fn is_not_committed(&self, key: EntityIndex) -> bool {
         self.entities
             .get(key.index as usize)
-            .unwrap()
-            .committed
-            .not()
+            .is_none_or(|it| it.committed.not())
     }
 
     pub(crate) fn delete_entity_data(&mut self, key: EntityIndex) {
         trace!("deleting entity data {}", key);
         let key = key.index as usize;
-        self.entities.get_mut(key).unwrap().exists = false;
+        let Some(entity) = self.entities.get_mut(key) else {
+            return;
+        };
+        entity.exists = false;
+        if entity.generation.is_last() {
+            trace!("retiring entity slot {}", key);
+            return;
+        }
         if key == self.allocation_boundary - 1 {
             self.allocation_boundary -= 1;
         } else {
@@ -190,10 +252,9 @@ impl EntityStorage {
 
     pub(crate) fn mark_committed(&mut self, entity_key: EntityIndex) {
         trace!("marking entity committed {}", entity_key);
-        self.entities
-            .get_mut(entity_key.index as usize)
-            .unwrap()
-            .committed = true;
+        if let Some(entity) = self.entities.get_mut(entity_key.index as usize) {
+            entity.committed = true;
+        }
     }
 
     pub(crate) fn get_all(&self) -> impl Iterator<Item = InternalEntityKey> + '_ {
@@ -208,3 +269,100 @@ impl EntityStorage {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::entity_storage::ValidateUncommitted::AllowUncommitted;
+
+    const SLOT_REUSES: usize = u16::MAX as usize + 10;
+
+    #[test]
+    fn trailing_slots_released_on_shrink() {
+        let mut storage = EntityStorage::with_capacity(4);
+        let entities = (0..100).map(|_| storage.new_entity()).collect::<Vec<_>>();
+        for entity in &entities[2..] {
+            storage.delete_entity_data(entity.index);
+        }
+
+        assert_eq!(storage.shrink_to_fit(), 4);
+        assert!(storage.holes.is_empty());
+        assert_eq!(storage.validate(entities[1], AllowUncommitted), Ok(()));
+    }
+
+    #[test]
+    fn released_slots_not_accessed() {
+        let mut storage = EntityStorage::with_capacity(4);
+        let entities = (0..100).map(|_| storage.new_entity()).collect::<Vec<_>>();
+        for entity in &entities {
+            storage.delete_entity_data(entity.index);
+        }
+        storage.shrink_to_fit();
+        let stale = entities[99];
+
+        assert_eq!(storage.validate(stale, AllowUncommitted), Err(NotExists));
+        assert!(storage.is_not_committed(stale.index));
+        storage.mark_committed(stale.index);
+        storage.delete_entity_data(stale.index);
+        assert_eq!(storage.capacity(), 4);
+    }
+
+    #[test]
+    fn released_slot_not_reused_with_stale_generation() {
+        let mut storage = EntityStorage::with_capacity(4);
+        let entities = (0..8).map(|_| storage.new_entity()).collect::<Vec<_>>();
+        for entity in &entities {
+            storage.delete_entity_data(entity.index);
+        }
+        storage.shrink_to_fit();
+
+        let reused = (0..8).map(|_| storage.new_entity()).collect::<Vec<_>>();
+
+        assert_eq!(reused[7].index, entities[7].index);
+        assert!(reused[7].generation > entities[7].generation);
+        assert_eq!(storage.validate(entities[7], AllowUncommitted), Err(IsStale));
+    }
+
+    #[test]
+    fn generation_grows_while_slot_reused() {
+        let mut storage = EntityStorage::with_capacity(4);
+        let mut prev = storage.new_entity();
+        for _ in 0..u16::MAX - 1 {
+            storage.delete_entity_data(prev.index);
+            let next = storage.new_entity();
+            assert_eq!(next.index, prev.index);
+            assert_eq!(next.generation, prev.generation.to_next_generation());
+            prev = next;
+        }
+    }
+
+    #[test]
+    fn slot_retired_instead_of_generation_wrap() {
+        let mut storage = EntityStorage::with_capacity(4);
+        let first = storage.new_entity();
+        let mut last = first;
+        for _ in 0..SLOT_REUSES {
+            storage.delete_entity_data(last.index);
+            last = storage.new_entity();
+        }
+        assert_ne!(last.index, first.index);
+        assert_eq!(storage.validate(first, AllowUncommitted), Err(NotExists));
+        assert_eq!(storage.validate(last, AllowUncommitted), Ok(()));
+    }
+
+    #[test]
+    fn retired_slot_not_reused_as_hole() {
+        let mut storage = EntityStorage::with_capacity(4);
+        let first = storage.new_entity();
+        // keeps the pounded slot away from the allocation boundary
+        storage.new_entity();
+        let mut last = first;
+        for _ in 0..SLOT_REUSES {
+            storage.delete_entity_data(last.index);
+            last = storage.new_entity();
+        }
+        for _ in 0..16 {
+            assert_ne!(storage.new_entity().index, first.index);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::backtrace::Backtrace;
 use std::borrow::Cow;
 use log::{error};
 use std::cell::RefCell;
@@ -6,15 +7,16 @@ use std::fmt::Formatter;
 use std::marker::PhantomData;
 use std::mem;
 use std::ops::AddAssign;
+use std::panic::AssertUnwindSafe;
 use std::panic::RefUnwindSafe;
 use std::panic::UnwindSafe;
 
 use crate::internal::cause::Cause;
-use crate::internal::change_buffer::ChangeBuffer;
 use crate::internal::entity_key_generator::TemporaryEntityKeyStorage;
 use crate::internal::entity_storage::EntityStorage;
 use crate::panic_hook::catch_unwind_detailed;
 use crate::panic_hook::DetailedError;
+use crate::run_condition::ModuleKey;
 use crate::Ctx;
 use crate::StableWorld;
 use crate::VolatileWorld;
@@ -42,6 +44,20 @@ pub struct ExecutionError {
     pub cause: Cause,
 }
 
+impl ExecutionError {
+    // broken invariant detected by the framework itself, not a panic of user code
+    pub(crate) fn violation(message: String, cause: Cause) -> ExecutionError {
+        error!("{}", message);
+        ExecutionError {
+            details: DetailedError {
+                backtrace: Backtrace::disabled(),
+                message,
+            },
+            cause,
+        }
+    }
+}
+
 impl Display for ExecutionError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.details)?;
@@ -50,11 +66,13 @@ impl Display for ExecutionError {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn invoke_user_code<R, P: RefUnwindSafe>(
     volatile: &mut VolatileWorld,
     stable: &StableWorld,
     entity_storage: &mut EntityStorage,
     handler_name: &'static str,
+    module: Option<ModuleKey>,
     causes: impl IntoIterator<Item = Cause>,
     code: impl IntoIterator<Item = impl Code<P, R>>,
     mut result_handler: impl FnMut(R),
@@ -64,20 +82,23 @@ pub(crate) fn invoke_user_code<R, P: RefUnwindSafe>(
     let prev_cause = mem::replace(&mut volatile.current_cause, new_cause.clone());
     let mut result = ExecutionResult::new();
     for code in code {
+        // buffer is reused between invocations to keep allocated pools and capacity
+        let mut changes = mem::take(&mut volatile.change_buffer);
+        changes.entity_key_generator = TemporaryEntityKeyStorage::new();
+        let changes_ref = AssertUnwindSafe(RefCell::new(&mut changes));
         let code_result = catch_unwind_detailed(|| {
-            let mut changes = ChangeBuffer::new(TemporaryEntityKeyStorage::new());
-            let changes_ref = RefCell::new(&mut changes);
-            let ctx = Ctx::new(payload, stable, entity_storage, &changes_ref);
-            let code_result = code.invoke(ctx);
-            (changes, code_result)
+            let ctx = Ctx::new(payload, module, stable, entity_storage, &changes_ref);
+            code.invoke(ctx)
         });
         match code_result {
             Ok(result) => {
-                let (changes, result) = result;
                 result_handler(result);
                 changes.apply_to(volatile, entity_storage, &stable.component_mappings);
+                volatile.change_buffer = changes;
             }
             Err(err) => {
+                changes.changes.clear();
+                volatile.change_buffer = changes;
                 error!(
                     "handler {:?} failed: {}\n cause: {}",
                     handler_name, &err, &new_cause
@@ -14,6 +14,15 @@ pub(crate) struct Filter {
     pub(crate) matched_entities: Option<HashSet<InternalEntityKey>>,
     pub(crate) appear_events: Option<HashMap<InternalEntityKey, OptTinyVec<Cause>>>,
     pub(crate) disappear_events: Option<HashMap<InternalEntityKey, OptTinyVec<Cause>>>,
+    pub(crate) tracking: Tracking,
+}
+
+// number of registrations relying on each kind of tracking, it's dropped once none is left
+#[derive(Default)]
+pub(crate) struct Tracking {
+    matched_entities: usize,
+    appear_events: usize,
+    disappear_events: usize,
 }
 
 impl Filter {
@@ -22,6 +31,18 @@ impl Filter {
             matched_entities.remove(&entity);
         }
     }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        if let Some(matched_entities) = &mut self.matched_entities {
+            matched_entities.shrink_to_fit();
+        }
+        if let Some(appear_events) = &mut self.appear_events {
+            appear_events.shrink_to_fit();
+        }
+        if let Some(disappear_events) = &mut self.disappear_events {
+            disappear_events.shrink_to_fit();
+        }
+    }
 }
 
 impl Filter {
@@ -30,6 +51,7 @@ impl Filter {
         entity_storage: &EntityStorage,
         component_mappings: &ComponentMappingStorage,
     ) -> &mut HashSet<InternalEntityKey> {
+        self.tracking.matched_entities += 1;
         if self.matched_entities.is_none() {
             self.matched_entities = Some(Default::default());
             self.pre_fill_matched_entities(entity_storage, component_mappings);
@@ -53,14 +75,37 @@ impl Filter {
     }
 
     pub(crate) fn track_appear_events(&mut self) {
+        self.tracking.appear_events += 1;
         if self.appear_events.is_none() {
             self.appear_events = Some(Default::default());
         }
     }
 
     pub(crate) fn track_disappear_events(&mut self) {
+        self.tracking.disappear_events += 1;
         if self.disappear_events.is_none() {
             self.disappear_events = Some(Default::default());
         }
     }
+
+    pub(crate) fn untrack_matched_entities(&mut self) {
+        self.tracking.matched_entities -= 1;
+        if self.tracking.matched_entities == 0 {
+            self.matched_entities = None;
+        }
+    }
+
+    pub(crate) fn untrack_appear_events(&mut self) {
+        self.tracking.appear_events -= 1;
+        if self.tracking.appear_events == 0 {
+            self.appear_events = None;
+        }
+    }
+
+    pub(crate) fn untrack_disappear_events(&mut self) {
+        self.tracking.disappear_events -= 1;
+        if self.tracking.disappear_events == 0 {
+            self.disappear_events = None;
+        }
+    }
 }
@@ -59,6 +59,12 @@ impl FilterManager {
         panic!("filter not initialized: {}", key)
     }
 
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for filter in self.owned.iter_mut() {
+            filter.shrink_to_fit();
+        }
+    }
+
     pub(crate) fn get_filter_by_key(&self, key: InternalFilterKey) -> &Filter {
         return self.owned.get(&key).unwrap();
     }
@@ -80,6 +86,7 @@ impl FilterManager {
                 matched_entities: None,
                 appear_events: None,
                 disappear_events: None,
+                tracking: Default::default(),
             }
         });
         self.by_key_ptr.insert(key_ptr, filter_index);
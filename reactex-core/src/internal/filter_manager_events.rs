@@ -1,3 +1,4 @@
+use crate::component::ComponentType;
 use crate::internal::cause::Cause;
 use crate::internal::component_key::ComponentKey;
 use crate::internal::entity_component_index::EntityComponentIndex;
@@ -8,22 +9,31 @@ use log::trace;
 use std::collections::HashSet;
 
 impl FilterManager {
-    pub(crate) fn on_component_added(
+    // all components added to the entity in the transaction are evaluated at once,
+    // so filters see only the final combination
+    pub(crate) fn on_components_added(
         &mut self,
         entity_component_index: &EntityComponentIndex,
-        change: FilterComponentChange,
+        entity: InternalEntityKey,
+        changes: Vec<FilterComponentChange>,
     ) {
-        trace!("on_component_added {}", change.component_key);
-        let filters = self
-            .by_component_type
-            .get_mut(&change.component_key.component_type)
-            .into_iter()
-            .flat_map(|it| it.iter());
+        trace!("on_components_added {}", entity);
+        let mut visited = HashSet::new();
+        let filters: Vec<_> = changes
+            .iter()
+            .flat_map(|change| {
+                self.by_component_type
+                    .get(&change.component_key.component_type)
+                    .into_iter()
+                    .flatten()
+            })
+            .copied()
+            .filter(|it| visited.insert(*it))
+            .collect();
+        let present: HashSet<_> =
+            HashSet::from_iter(entity_component_index.get_component_types(entity.index));
         for filter in filters {
-            let filter = self.owned.get_mut(filter).unwrap();
-            let present: HashSet<_> = HashSet::from_iter(
-                entity_component_index.get_component_types(change.component_key.entity.index),
-            );
+            let filter = self.owned.get_mut(&filter).unwrap();
             let matches = filter
                 .criteria
                 .component_types
@@ -36,15 +46,71 @@ impl FilterManager {
             let mut events = false;
 
             if let Some(matched) = &mut filter.matched_entities {
-                matched.insert(change.component_key.entity);
+                matched.insert(entity);
                 events = true;
             }
             if let Some(appear_events) = &mut filter.appear_events {
-                appear_events
-                    .entry(change.component_key.entity)
-                    .or_default()
+                let causes = changes
+                    .iter()
+                    .filter(|change| {
+                        filter
+                            .criteria
+                            .component_types
+                            .contains(&change.component_key.component_type)
+                    })
                     // TODO consider get rid of clones
-                    .extend(change.causes.iter().cloned());
+                    .flat_map(|change| change.causes.iter().cloned());
+                appear_events.entry(entity).or_default().extend(causes);
+                events = true;
+            }
+
+            if events {
+                self.with_new_appear_events.insert(filter.unique_key);
+            }
+        }
+    }
+
+    // entities of a spawned batch are new and have the same components, so filters are
+    // evaluated once for all of them
+    pub(crate) fn on_batch_added(
+        &mut self,
+        component_types: &[ComponentType],
+        entities: &[InternalEntityKey],
+        cause: &Cause,
+    ) {
+        trace!("on_batch_added {} entities", entities.len());
+        let mut visited = HashSet::new();
+        let filters: Vec<_> = component_types
+            .iter()
+            .flat_map(|it| self.by_component_type.get(it).into_iter().flatten())
+            .copied()
+            .filter(|it| visited.insert(*it))
+            .collect();
+        for filter in filters {
+            let filter = self.owned.get_mut(&filter).unwrap();
+            let matches = filter
+                .criteria
+                .component_types
+                .iter()
+                .all(|ct| component_types.contains(ct));
+
+            if !matches {
+                continue;
+            }
+            let mut events = false;
+
+            if let Some(matched) = &mut filter.matched_entities {
+                matched.extend(entities.iter().copied());
+                events = true;
+            }
+            if let Some(appear_events) = &mut filter.appear_events {
+                appear_events.reserve(entities.len());
+                for entity in entities {
+                    appear_events
+                        .entry(*entity)
+                        .or_default()
+                        .push(cause.clone());
+                }
                 events = true;
             }
 
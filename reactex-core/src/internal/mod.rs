@@ -1,8 +1,11 @@
+pub(crate) mod aggregate_storage;
 pub(crate) mod cause;
 pub(crate) mod change_buffer;
+pub(crate) mod component_indexes;
 pub(crate) mod component_key;
 pub(crate) mod component_mappings;
 pub(crate) mod component_pool_manager;
+pub(crate) mod component_requirements;
 pub(crate) mod entity_component_index;
 pub(crate) mod entity_key_generator;
 pub(crate) mod entity_storage;
@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::any::TypeId;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::panic::RefUnwindSafe;
@@ -10,20 +11,28 @@ use crate::internal::execution::invoke_user_code;
 use crate::internal::execution::ExecutionResult;
 use crate::internal::execution::UserCode;
 use crate::internal::filter_manager::InternalFilterKey;
+use crate::internal::signal_storage::SignalDataKey;
+use crate::internal::world_extras::InternalEntityKey;
 use crate::internal::world_extras::Signal;
 use crate::internal::world_stable::StableWorld;
 use crate::internal::world_volatile::VolatileWorld;
+use crate::run_condition::Gates;
+use crate::run_condition::ModuleKey;
+use crate::utils::pools::SpecificPool;
 use crate::Ctx;
 
 pub(crate) trait AbstractSignalManager {
     fn invoke(
         &self,
         signal: Signal,
+        gates: &Gates,
         stable: &mut StableWorld,
         volatile: &mut VolatileWorld,
         entity_storage: &mut EntityStorage,
     ) -> ExecutionResult;
     fn as_any_mut(&mut self) -> AnySignalManager;
+    // returns filters of the removed entity handlers, one per handler
+    fn remove_handlers(&mut self, module: ModuleKey) -> Vec<InternalFilterKey>;
 }
 
 pub(crate) struct AnySignalManager<'a> {
@@ -38,11 +47,13 @@ impl<'a> AnySignalManager<'a> {
 
 pub(crate) struct EntitySignalHandler<T> {
     pub(crate) name: &'static str,
+    pub(crate) module: Option<ModuleKey>,
     pub(crate) callback: Box<EntitySignalCallback<T>>,
 }
 
 pub(crate) struct GlobalSignalHandler<T> {
     pub(crate) name: &'static str,
+    pub(crate) module: Option<ModuleKey>,
     pub(crate) callback: Box<GlobalSignalCallback<T>>,
 }
 
@@ -64,30 +75,41 @@ impl<T: RefUnwindSafe + 'static> AbstractSignalManager for SignalManager<T> {
     fn invoke(
         &self,
         signal: Signal,
+        gates: &Gates,
         stable: &mut StableWorld,
         volatile: &mut VolatileWorld,
         entity_storage: &mut EntityStorage,
     ) -> ExecutionResult {
-        let payload = volatile
-            .signal_storage
-            .payloads
-            .get_mut(&signal.payload_type)
-            .unwrap()
-            .specializable_mut()
-            .try_specialize::<T>()
-            .unwrap()
+        let payload = specific_pool::<T>(volatile)
             .del_and_get(&signal.data_key)
             .unwrap();
 
         let mut result = ExecutionResult::new();
 
-        for handler in &self.global_handlers {
+        let global_handlers = match signal.target {
+            None => &self.global_handlers[..],
+            Some(_) => &[],
+        };
+        for handler in global_handlers {
+            if !gates.is_open(
+                handler.name,
+                handler.module,
+                stable,
+                volatile,
+                entity_storage,
+                [signal.cause.clone()],
+                &mut result,
+            ) {
+                trace!("skip disabled global signal handler {}", handler.name);
+                continue;
+            }
             trace!("invoke global signal handler {}", handler.name);
             result += invoke_user_code(
                 volatile,
                 stable,
                 entity_storage,
                 handler.name,
+                handler.module,
                 [signal.cause.clone()],
                 [UserCode::new(|ctx| {
                     (handler.callback)(ctx);
@@ -99,18 +121,36 @@ impl<T: RefUnwindSafe + 'static> AbstractSignalManager for SignalManager<T> {
 
         for (filter, handlers) in &self.handlers {
             for handler in handlers {
+                if !gates.is_open(
+                    handler.name,
+                    handler.module,
+                    stable,
+                    volatile,
+                    entity_storage,
+                    [signal.cause.clone()],
+                    &mut result,
+                ) {
+                    trace!("skip disabled signal handler {}", handler.name);
+                    continue;
+                }
                 if let Some(matched_entities) = &stable
                     .filter_manager
                     .get_filter_by_key(*filter)
                     .matched_entities
                 {
+                    let entities: Box<dyn Iterator<Item = &InternalEntityKey>> =
+                        match &signal.target {
+                            Some(target) => Box::new(matched_entities.get(target).into_iter()),
+                            None => Box::new(matched_entities.iter()),
+                        };
                     result += invoke_user_code(
                         volatile,
                         stable,
                         entity_storage,
                         handler.name,
+                        handler.module,
                         [signal.cause.clone()],
-                        matched_entities.iter().map(|entity| {
+                        entities.map(|entity| {
                             trace!("invoke signal handler {} for {}", handler.name, entity);
                             UserCode::new(|ctx| (handler.callback)(ctx, entity.export()))
                         }),
@@ -127,6 +167,33 @@ impl<T: RefUnwindSafe + 'static> AbstractSignalManager for SignalManager<T> {
     fn as_any_mut(&mut self) -> AnySignalManager {
         AnySignalManager { any: self }
     }
+
+    fn remove_handlers(&mut self, module: ModuleKey) -> Vec<InternalFilterKey> {
+        self.global_handlers.retain(|it| it.module != Some(module));
+        let mut filters = vec![];
+        for (filter, handlers) in &mut self.handlers {
+            handlers.retain(|it| {
+                let removed = it.module == Some(module);
+                if removed {
+                    filters.push(*filter);
+                }
+                !removed
+            });
+        }
+        self.handlers.retain(|_, it| !it.is_empty());
+        filters
+    }
+}
+
+fn specific_pool<T: 'static>(volatile: &mut VolatileWorld) -> &mut SpecificPool<SignalDataKey, T> {
+    volatile
+        .signal_storage
+        .payloads
+        .get_mut(&TypeId::of::<T>())
+        .unwrap()
+        .specializable_mut()
+        .try_specialize::<T>()
+        .unwrap()
 }
 
 pub(crate) type GlobalSignalCallback<T> = dyn Fn(Ctx<T>) + RefUnwindSafe;
@@ -1,5 +1,6 @@
 use crate::internal::cause::Cause;
 use crate::internal::signal_storage::SignalDataKey;
+use crate::internal::world_extras::InternalEntityKey;
 use crate::internal::world_extras::Signal;
 use std::any::{type_name, TypeId};
 use std::collections::VecDeque;
@@ -10,12 +11,18 @@ pub(crate) struct SignalQueue {
 }
 
 impl SignalQueue {
-    pub(crate) fn signal<T: 'static>(&mut self, data: SignalDataKey, current_cause: &Cause) {
+    pub(crate) fn signal<T: 'static>(
+        &mut self,
+        data: SignalDataKey,
+        current_cause: &Cause,
+        target: Option<InternalEntityKey>,
+    ) {
         self.signals.push_back(Signal {
             payload_type: TypeId::of::<T>(),
             payload_type_name: type_name::<T>(),
             data_key: data,
             cause: current_cause.clone(),
+            target,
         })
     }
 }
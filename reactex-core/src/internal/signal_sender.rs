@@ -1,6 +1,7 @@
 use crate::internal::cause::Cause;
 use crate::internal::signal_queue::SignalQueue;
 use crate::internal::signal_storage::SignalStorage;
+use crate::internal::world_extras::InternalEntityKey;
 use log::trace;
 use std::any::type_name;
 use std::any::TypeId;
@@ -13,6 +14,14 @@ pub(crate) struct SignalSender<'a> {
 
 impl<'a> SignalSender<'a> {
     pub(crate) fn signal<T: 'static>(&mut self, payload: T) {
+        self.send(payload, None);
+    }
+
+    pub(crate) fn signal_to<T: 'static>(&mut self, target: InternalEntityKey, payload: T) {
+        self.send(payload, Some(target));
+    }
+
+    fn send<T: 'static>(&mut self, payload: T, target: Option<InternalEntityKey>) {
         trace!("enqueueing signal");
         let cause = self.current_cause;
         let data_key = self
@@ -24,6 +33,6 @@ impl<'a> SignalSender<'a> {
             .try_specialize::<T>()
             .unwrap()
             .add(payload);
-        self.signal_queue.signal::<T>(data_key, cause);
+        self.signal_queue.signal::<T>(data_key, cause, target);
     }
 }
@@ -1,14 +1,20 @@
+use crate::component::ComponentType;
 use crate::entity_key::EntityKey;
 use crate::filter::FilterDesc;
+use crate::internal::filter_manager::InternalFilterKey;
 use crate::internal::signal_manager::EntitySignalHandler;
 use crate::internal::signal_manager::GlobalSignalHandler;
 use crate::internal::signal_storage::SignalDataKey;
 use crate::internal::world_core::World;
 use crate::internal::world_extras::EventHandler;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::run_condition::ModuleKey;
 use crate::utils::pools::SpecificPool;
 use crate::Ctx;
 use log::trace;
 use std::any::TypeId;
+use std::collections::HashMap;
+use to_vec::ToVec;
 
 use std::panic::RefUnwindSafe;
 
@@ -40,11 +46,13 @@ impl World {
             .payloads
             .entry(TypeId::of::<T>())
             .or_insert_with(|| Box::new(SpecificPool::<SignalDataKey, T>::new()));
+        let module = self.immutable.gates.register_handler(name, true);
         self.immutable
             .get_signal_manager::<T>()
             .global_handlers
             .push(GlobalSignalHandler {
                 name,
+                module,
                 callback: Box::new(callback),
             });
     }
@@ -69,6 +77,7 @@ impl World {
         let filter = self.stable.filter_manager.get_filter_mut(filter);
         filter.track_matched_entities(&self.entity_storage, &self.stable.component_mappings);
         let filter_key = filter.unique_key;
+        let module = self.immutable.gates.register_handler(name, true);
         self.immutable
             .get_signal_manager::<T>()
             .handlers
@@ -76,6 +85,7 @@ impl World {
             .or_default()
             .push(EntitySignalHandler {
                 name,
+                module,
                 callback: Box::new(callback),
             });
     }
@@ -84,17 +94,21 @@ impl World {
         &mut self,
         name: &'static str,
         filter_key: FilterDesc,
+        gated: bool,
         callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
     ) {
         let filter = self.stable.filter_manager.get_filter_mut(filter_key);
         filter.track_disappear_events();
         let filter_key = filter.unique_key;
+        let module = self.immutable.gates.register_handler(name, gated);
         self.immutable
             .on_disappear
             .entry(filter_key)
             .or_default()
             .push(EventHandler {
                 name,
+                module,
+                gated,
                 callback: Box::new(callback),
             });
     }
@@ -103,18 +117,152 @@ impl World {
         &mut self,
         name: &'static str,
         filter_key: FilterDesc,
+        gated: bool,
         callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
     ) {
         let filter = self.stable.filter_manager.get_filter_mut(filter_key);
         filter.track_appear_events();
         let filter_key = filter.unique_key;
+        let module = self.immutable.gates.register_handler(name, gated);
         self.immutable
             .on_appear
             .entry(filter_key)
             .or_default()
             .push(EventHandler {
                 name,
+                module,
+                gated,
                 callback: Box::new(callback),
             });
     }
+
+    // invoked once per transaction step for an entity which had any of the components modified
+    pub(crate) fn add_modify_handler(
+        &mut self,
+        name: &'static str,
+        component_types: &[ComponentType],
+        gated: bool,
+        callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        let index = self.immutable.modify_handlers.len();
+        let module = self.immutable.gates.register_handler(name, gated);
+        self.immutable.modify_handlers.push(EventHandler {
+            name,
+            module,
+            gated,
+            callback: Box::new(callback),
+        });
+        for component_type in component_types {
+            self.immutable
+                .on_modify
+                .entry(*component_type)
+                .or_default()
+                .push(index);
+        }
+    }
+
+    // entities existing when an index or an aggregate is registered, e.g. by a replaced module
+    pub(crate) fn existing_entities(
+        &self,
+        component_types: &[ComponentType],
+    ) -> Vec<InternalEntityKey> {
+        let mappings = &self.stable.component_mappings;
+        self.entity_storage
+            .get_all()
+            .filter(|entity| {
+                component_types
+                    .iter()
+                    .all(|it| mappings.has_component_no_validation(entity.index, *it))
+            })
+            .to_vec()
+    }
+
+    // drops handlers, aggregates and indexes registered by the module along with filter tracking
+    // they relied on
+    pub(crate) fn remove_module_registrations(&mut self, module: ModuleKey) {
+        let filter_manager = &mut self.stable.filter_manager;
+        for filter in self.stable.aggregates.remove_module(module) {
+            let filter = filter_manager.get_filter_internal(filter);
+            filter.untrack_appear_events();
+            filter.untrack_disappear_events();
+        }
+        self.stable.indexes.remove_module(module);
+        #[cfg(feature = "spatial")]
+        self.stable.spatial.remove_module(module);
+        for sources in self.immutable.derivations.values_mut() {
+            sources.retain(|(_, it)| *it != Some(module));
+        }
+        self.immutable.derivations.retain(|_, it| !it.is_empty());
+        for manager in self.immutable.signal_managers.values_mut() {
+            for filter in manager.remove_handlers(module) {
+                filter_manager
+                    .get_filter_internal(filter)
+                    .untrack_matched_entities();
+            }
+        }
+        for filter in remove_event_handlers(&mut self.immutable.on_appear, module) {
+            filter_manager
+                .get_filter_internal(filter)
+                .untrack_appear_events();
+        }
+        for filter in remove_event_handlers(&mut self.immutable.on_disappear, module) {
+            filter_manager
+                .get_filter_internal(filter)
+                .untrack_disappear_events();
+        }
+        for handlers in self.immutable.state_handlers.values_mut() {
+            for filter in handlers.remove_module_handlers(module) {
+                filter_manager
+                    .get_filter_mut(filter)
+                    .untrack_matched_entities();
+            }
+        }
+
+        // `on_modify` refers to modify handlers by index, so the indices are shifted
+        let mut kept = 0;
+        let indices = self
+            .immutable
+            .modify_handlers
+            .iter()
+            .map(|handler| {
+                if handler.module == Some(module) {
+                    return None;
+                }
+                kept += 1;
+                Some(kept - 1)
+            })
+            .to_vec();
+        self.immutable
+            .modify_handlers
+            .retain(|it| it.module != Some(module));
+        for handlers in self.immutable.on_modify.values_mut() {
+            handlers.retain_mut(|index| match indices[*index] {
+                Some(new_index) => {
+                    *index = new_index;
+                    true
+                }
+                None => false,
+            });
+        }
+        self.immutable.on_modify.retain(|_, it| !it.is_empty());
+    }
+}
+
+// returns filters of the removed handlers, one per handler
+fn remove_event_handlers(
+    handlers: &mut HashMap<InternalFilterKey, Vec<EventHandler>>,
+    module: ModuleKey,
+) -> Vec<InternalFilterKey> {
+    let mut filters = vec![];
+    for (filter, handlers) in handlers.iter_mut() {
+        handlers.retain(|it| {
+            let removed = it.module == Some(module);
+            if removed {
+                filters.push(*filter);
+            }
+            !removed
+        });
+    }
+    handlers.retain(|_, it| !it.is_empty());
+    filters
 }
@@ -47,6 +47,37 @@ impl World {
         world
     }
 
+    // stand-in while the world is moved out, e.g. to be reconfigured
+    pub(crate) fn empty() -> Self {
+        Self {
+            immutable: ImmutableWorld::new(),
+            volatile: VolatileWorld::new(),
+            stable: StableWorld::new(),
+            entity_storage: EntityStorage::with_capacity(0),
+            tx: 0,
+        }
+    }
+
+    // must not be called while there are uncommitted changes
+    pub(crate) fn shrink_to_fit(&mut self) {
+        let capacity = self.entity_storage.shrink_to_fit();
+        self.volatile.entity_component_index.shrink(capacity);
+        self.volatile.entities_to_commit.shrink_to_fit();
+        self.volatile.components_to_add.shrink_to_fit();
+        self.volatile.components_to_modify.shrink_to_fit();
+        self.volatile.component_data_uncommitted.shrink_to_fit();
+        self.stable.component_data.shrink_to_fit();
+        self.stable.component_mappings.shrink_to_fit();
+        self.stable.filter_manager.shrink_to_fit();
+        self.stable.aggregates.shrink_to_fit();
+        self.stable.indexes.shrink_to_fit();
+        self.stable.timers.shrink_to_fit();
+        #[cfg(feature = "uuid")]
+        self.stable.stable_ids.shrink_to_fit();
+        #[cfg(feature = "spatial")]
+        self.stable.spatial.shrink_to_fit();
+    }
+
     fn register_filter(&mut self, filter: FilterDesc) {
         self.stable
             .filter_manager
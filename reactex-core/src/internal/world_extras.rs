@@ -1,7 +1,9 @@
+use crate::component::ComponentType;
 use crate::entity_key::EntityKey;
 use crate::internal::cause::Cause;
 use crate::internal::component_pool_manager::TempComponentDataKey;
 use crate::internal::signal_storage::SignalDataKey;
+use crate::run_condition::ModuleKey;
 use crate::utils::opt_tiny_vec::OptTinyVec;
 use crate::Ctx;
 use std::any::Any;
@@ -26,6 +28,9 @@ impl<TKey> DeleteQueue<TKey> {
     }
 }
 
+// type-erased component value, e.g. cloned or deserialized one
+pub(crate) type ComponentValue = (ComponentType, Box<dyn Any>);
+
 pub(crate) struct ComponentAdd {
     pub(crate) data: TempComponentDataKey,
     pub(crate) cause: Cause,
@@ -33,6 +38,9 @@ pub(crate) struct ComponentAdd {
 
 pub(crate) struct EventHandler {
     pub(crate) name: &'static str,
+    pub(crate) module: Option<ModuleKey>,
+    // framework-maintained handlers (derived components, hooks) ignore switches and run conditions
+    pub(crate) gated: bool,
     pub(crate) callback: Box<dyn Fn(Ctx, EntityKey) + RefUnwindSafe>,
 }
 
@@ -41,6 +49,8 @@ pub(crate) struct Signal {
     pub(crate) payload_type_name: &'static str,
     pub(crate) data_key: SignalDataKey,
     pub(crate) cause: Cause,
+    // only entity handlers of the target are invoked for targeted signals
+    pub(crate) target: Option<InternalEntityKey>,
 }
 
 #[derive(Debug)]
@@ -49,11 +59,12 @@ pub(crate) enum ComponentEventType {
     Disappear,
 }
 
-pub(crate) struct ComponentModify {
-    pub(crate) callback: Box<dyn FnOnce(&mut dyn Any)>,
+pub(crate) enum ComponentModify {
+    Callback(Box<dyn FnOnce(&mut dyn Any)>),
+    Set(TempComponentDataKey),
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub(crate) struct InternalEntityKey {
     pub(crate) index: EntityIndex,
     pub(crate) generation: EntityGeneration,
@@ -73,10 +84,10 @@ impl Debug for InternalEntityKey {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-pub(crate) struct EntityGeneration(u16);
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Ord, PartialOrd)]
+pub(crate) struct EntityGeneration(pub(crate) u16);
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Ord, PartialOrd)]
 pub(crate) struct EntityIndex {
     pub(crate) index: u32,
 }
@@ -107,6 +118,15 @@ impl EntityGeneration {
     }
 
     pub fn to_next_generation(self) -> Self {
-        Self(self.0 + 1)
+        Self(
+            self.0
+                .checked_add(1)
+                .expect("framework BUG: exhausted entity slot reused"),
+        )
+    }
+
+    // slot with the last generation can't be reused without making stale keys valid again
+    pub fn is_last(self) -> bool {
+        self.0 == u16::MAX
     }
 }
@@ -1,7 +1,11 @@
+use crate::component::ComponentType;
 use crate::internal::filter_manager::InternalFilterKey;
 use crate::internal::signal_manager::AbstractSignalManager;
 use crate::internal::signal_manager::SignalManager;
 use crate::internal::world_extras::EventHandler;
+use crate::run_condition::Gates;
+use crate::run_condition::ModuleKey;
+use crate::state::StateHandlers;
 use std::any::TypeId;
 use std::collections::HashMap;
 use std::panic::RefUnwindSafe;
@@ -10,6 +14,13 @@ pub struct ImmutableWorld {
     pub(crate) signal_managers: HashMap<TypeId, Box<dyn AbstractSignalManager>>,
     pub(crate) on_appear: HashMap<InternalFilterKey, Vec<EventHandler>>,
     pub(crate) on_disappear: HashMap<InternalFilterKey, Vec<EventHandler>>,
+    pub(crate) modify_handlers: Vec<EventHandler>,
+    // indices of `modify_handlers` by watched component type
+    pub(crate) on_modify: HashMap<ComponentType, Vec<usize>>,
+    // source types of derived component types, along with modules which derive them
+    pub(crate) derivations: HashMap<ComponentType, Vec<(ComponentType, Option<ModuleKey>)>>,
+    pub(crate) state_handlers: HashMap<ComponentType, StateHandlers>,
+    pub(crate) gates: Gates,
 }
 
 impl ImmutableWorld {
@@ -17,7 +28,12 @@ impl ImmutableWorld {
         Self {
             on_appear: Default::default(),
             on_disappear: Default::default(),
+            modify_handlers: Default::default(),
+            on_modify: Default::default(),
+            derivations: Default::default(),
+            state_handlers: Default::default(),
             signal_managers: Default::default(),
+            gates: Default::default(),
         }
     }
 
@@ -23,12 +23,15 @@ pub(crate) fn configure_pipeline(world: &mut World) {
     let mut schedule_destroyed_entities_component_removal = 0;
     let mut generate_disappear_events = 0;
     let mut flush_component_addition = 0;
+    let mut invoke_exit_handlers = 0;
+    let mut flush_component_modification = 0;
 
+    step_resulted!(world, advance_timers_every_transaction, &mut 0);
     step_resulted!(world, invoke_signal_handler, &mut invoke_signal_handler);
     step_simple__!(world, schedule_destroyed_entities_component_removal, &mut schedule_destroyed_entities_component_removal);
     step_simple__!(world, generate_disappear_events, &mut generate_disappear_events);
     step_resulted!(world, invoke_disappear_handlers, &mut 0);
-    step_simple__!(world, flush_component_removals, &mut 0);
+    step_resulted!(world, flush_component_removals, &mut 0);
     add_goto(world, "check_destroyed_entities_early",
         |world| !world.entities_to_destroy.before_disappear.is_empty(),
         schedule_destroyed_entities_component_removal,
@@ -39,8 +42,11 @@ pub(crate) fn configure_pipeline(world: &mut World) {
     );
     step_simple__!(world, flush_entity_destroy_actions, &mut 0);
     step_simple__!(world, flush_entity_create_actions, &mut 0);
-    step_simple__!(world, flush_component_addition, &mut flush_component_addition);
-    step_simple__!(world, flush_component_modification, &mut 0);
+    step_resulted!(world, flush_component_addition, &mut flush_component_addition);
+    step_resulted!(world, invoke_exit_handlers, &mut invoke_exit_handlers);
+    step_resulted!(world, flush_component_modification, &mut flush_component_modification);
+    step_resulted!(world, invoke_modify_handlers, &mut 0);
+    step_resulted!(world, invoke_enter_handlers, &mut 0);
     step_resulted!(world, invoke_appear_handlers, &mut 0);
     add_goto(world, "check_destroyed_entities_late",
         |world| !world.entities_to_destroy.before_disappear.is_empty(),
@@ -51,9 +57,17 @@ pub(crate) fn configure_pipeline(world: &mut World) {
         generate_disappear_events,
     );
     add_goto(world, "check_added_components",
-        |world| !world.components_to_add.is_empty(),
+        |world| !world.components_to_add.is_empty() || !world.batches_to_add.is_empty(),
         flush_component_addition,
     );
+    add_goto(world, "check_state_transitions",
+        |world| !world.state_transitions.is_empty(),
+        invoke_exit_handlers,
+    );
+    add_goto(world, "check_modified_components",
+        |world| !world.components_to_modify.is_empty(),
+        flush_component_modification,
+    );
     add_goto( world, "check_signals",
         |world| !world.signal_queue.signals.is_empty(),
         invoke_signal_handler,
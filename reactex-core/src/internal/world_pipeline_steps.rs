@@ -1,13 +1,23 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::mem;
 
 use log::trace;
+use to_vec::ToVec;
 
 use crate::{ExecutionResult, World};
+use crate::bundle::EcsBundle;
+use crate::bundle::PersistedBatch;
+use crate::bundle::StableBundleTarget;
+use crate::internal::cause::Cause;
 use crate::internal::component_key::ComponentKey;
+use crate::internal::execution::ExecutionError;
 use crate::internal::execution::{invoke_user_code, UserCode};
 use crate::internal::filter_manager_events::FilterComponentChange;
+use crate::internal::world_extras::ComponentAdd;
 use crate::internal::world_extras::ComponentEventType;
+use crate::internal::world_extras::ComponentModify;
+use crate::internal::world_extras::InternalEntityKey;
 use crate::utils::opt_tiny_vec::OptTinyVec;
 
 impl World {
@@ -20,31 +30,94 @@ impl World {
         }
     }
 
-    pub(crate) fn flush_component_modification(&mut self) {
+    pub(crate) fn flush_component_modification(&mut self, result: &mut ExecutionResult) {
+        let mut aggregates = mem::take(&mut self.stable.aggregates);
+        let mut indexes = mem::take(&mut self.stable.indexes);
         for (component_key, modifications) in mem::take(&mut self.volatile.components_to_modify) {
             trace!("flush component notification {}", component_key);
+            let component_type = component_key.component_type;
             let data = self
                 .stable
-                .get_component_mapping_mut(component_key.component_type)
+                .get_component_mapping_mut(component_type)
                 .get(&component_key.entity.index);
-            let Some(&data) = data else {
-                continue;
-            };
-            let value = self
-                .stable
-                .component_data
-                .get_pool_mut(component_key.component_type)
-                .get_any_mut(&data);
-            let Some(value) = value else {
+            let Some(mut data) = data.copied() else {
                 continue;
             };
-            for modification in modifications {
-                (modification.callback)(value);
+            for handler in self.immutable.on_modify.get(&component_type).into_iter().flatten() {
+                self.volatile
+                    .modify_events
+                    .entry(*handler)
+                    .or_default()
+                    .entry(component_key.entity)
+                    .or_default()
+                    .extend(modifications.iter().map(|(_, cause)| cause.clone()));
+            }
+            #[cfg(feature = "uuid")]
+            self.stable.unindex_stable_id(component_key);
+            for (modification, _) in modifications {
+                match modification {
+                    ComponentModify::Callback(callback) => {
+                        let value = self
+                            .stable
+                            .component_data
+                            .get_pool_mut(component_type)
+                            .get_any_mut(&data);
+                        if let Some(value) = value {
+                            callback(value);
+                        }
+                    }
+                    ComponentModify::Set(value) => {
+                        self.stable
+                            .component_data
+                            .get_pool_mut(component_type)
+                            .del(&data);
+                        data = self
+                            .stable
+                            .component_data_pumps
+                            .get(&component_type)
+                            .unwrap()
+                            .do_move(
+                                self.volatile
+                                    .change_buffer
+                                    .component_data
+                                    .get_pool_mut(component_type),
+                                self.stable.component_data.get_pool_mut(component_type),
+                                &value,
+                            );
+                    }
+                }
+            }
+            self.stable
+                .get_component_mapping_mut(component_type)
+                .insert(component_key.entity.index, data);
+            #[cfg(feature = "uuid")]
+            self.stable.index_stable_id(component_key);
+            #[cfg(feature = "spatial")]
+            self.stable.index_spatial(component_key);
+            if !aggregates.is_empty() {
+                aggregates.on_modified(component_type, component_key.entity, &self.stable);
+            }
+            if let Some(violation) = indexes.index(component_key, &self.stable) {
+                result.errors.push(ExecutionError::violation(
+                    violation,
+                    Cause::consequence("modify_indexed_component", []),
+                ));
             }
         }
+        self.stable.aggregates = aggregates;
+        self.stable.indexes = indexes;
+        // dropping values set to the components removed at the same transaction
+        self.volatile.change_buffer.component_data.clear();
     }
 
-    pub(crate) fn flush_component_addition(&mut self) {
+    pub(crate) fn flush_component_addition(&mut self, result: &mut ExecutionResult) {
+        for batch in mem::take(&mut self.volatile.batches_to_add) {
+            batch.flush(self, result);
+        }
+        self.add_required_components();
+        let mut indexes = mem::take(&mut self.stable.indexes);
+        let mut added_by_entity: HashMap<InternalEntityKey, Vec<FilterComponentChange>> =
+            HashMap::new();
         for (component_key, versions) in mem::take(&mut self.volatile.components_to_add) {
             trace!("flushing add component {}", component_key);
             let mut versions = versions.into_iter();
@@ -78,23 +151,181 @@ impl World {
                 *mapping, chosen_version,
                 "attempt to mark committed as committed"
             );
+            #[cfg(feature = "uuid")]
+            self.stable.index_stable_id(component_key);
+            #[cfg(feature = "spatial")]
+            self.stable.index_spatial(component_key);
+            if let Some(violation) = indexes.index(component_key, &self.stable) {
+                result.errors.push(ExecutionError::violation(
+                    violation,
+                    Cause::consequence("add_indexed_component", all_causes.iter().cloned()),
+                ));
+            }
+            self.stable
+                .timers
+                .on_added(component_key, all_causes.iter().cloned());
 
             self.volatile
                 .entity_component_index
                 .add_component_type(component_key.entity.index, component_key.component_type);
 
-            self.stable.filter_manager.on_component_added(
-                &self.volatile.entity_component_index,
-                FilterComponentChange {
+            added_by_entity
+                .entry(component_key.entity)
+                .or_default()
+                .push(FilterComponentChange {
                     component_key,
                     causes: all_causes,
-                },
+                });
+        }
+        self.stable.indexes = indexes;
+        for (entity, changes) in added_by_entity {
+            self.stable.filter_manager.on_components_added(
+                &self.volatile.entity_component_index,
+                entity,
+                changes,
             );
         }
         // deleting cancelled components (which entity or themselves was deleted at the same transaction)
         self.volatile.component_data_uncommitted.clear();
     }
 
+    // all entities of the batch have the same components, so pools and mappings are reserved and
+    // filters are matched once per batch
+    pub(crate) fn flush_batch_addition<T: EcsBundle>(
+        &mut self,
+        batch: PersistedBatch<T>,
+        result: &mut ExecutionResult,
+    ) {
+        trace!("flushing add batch of {} entities", batch.entities.len());
+        let len = batch.entities.len();
+        for component_type in T::COMPONENT_TYPES {
+            self.stable
+                .component_data
+                .get_pool_mut(*component_type)
+                .reserve(len);
+            self.stable
+                .get_component_mapping_mut(*component_type)
+                .reserve(len);
+        }
+        let mut indexes = mem::take(&mut self.stable.indexes);
+        for (entity, bundle) in batch.entities.iter().zip(batch.bundles) {
+            bundle.add_to(&mut StableBundleTarget {
+                entity: *entity,
+                stable: &mut self.stable,
+            });
+            for component_type in T::COMPONENT_TYPES {
+                let component_key = ComponentKey::new(*entity, *component_type);
+                #[cfg(feature = "uuid")]
+                self.stable.index_stable_id(component_key);
+                #[cfg(feature = "spatial")]
+                self.stable.index_spatial(component_key);
+                if let Some(violation) = indexes.index(component_key, &self.stable) {
+                    result.errors.push(ExecutionError::violation(
+                        violation,
+                        Cause::consequence("add_indexed_component", [batch.cause.clone()]),
+                    ));
+                }
+                self.stable
+                    .timers
+                    .on_added(component_key, [batch.cause.clone()].into_iter());
+                self.volatile
+                    .entity_component_index
+                    .add_component_type(entity.index, *component_type);
+            }
+        }
+        self.stable.indexes = indexes;
+        self.stable.filter_manager.on_batch_added(
+            T::COMPONENT_TYPES,
+            &batch.entities,
+            &batch.cause,
+        );
+        self.add_batch_requirements::<T>(&batch.entities, &batch.cause);
+    }
+
+    // requirements met by the bundle itself are skipped, the rest is added as usual
+    fn add_batch_requirements<T: EcsBundle>(
+        &mut self,
+        entities: &[InternalEntityKey],
+        cause: &Cause,
+    ) {
+        if self.stable.requirements.is_empty() {
+            return;
+        }
+        for component_type in T::COMPONENT_TYPES {
+            let required = self.stable.requirements.get_required(*component_type);
+            for requirement in required {
+                if T::COMPONENT_TYPES.contains(&requirement.component_type) {
+                    continue;
+                }
+                for entity in entities {
+                    let required_key = ComponentKey::new(*entity, requirement.component_type);
+                    if self.volatile.components_to_add.contains_key(&required_key) {
+                        continue;
+                    }
+                    trace!("add required component {}", required_key);
+                    let data = self
+                        .volatile
+                        .component_data_uncommitted
+                        .get_pool_mut(requirement.component_type)
+                        .add((requirement.constructor)());
+                    self.volatile
+                        .components_to_add
+                        .entry(required_key)
+                        .or_default()
+                        .push(ComponentAdd {
+                            data,
+                            cause: Cause::consequence("required_component", [cause.clone()]),
+                        });
+                }
+            }
+        }
+    }
+
+    fn add_required_components(&mut self) {
+        if self.stable.requirements.is_empty() {
+            return;
+        }
+        // required components may require others too
+        let mut queue = self.volatile.components_to_add.keys().copied().to_vec();
+        while let Some(component_key) = queue.pop() {
+            let entity = component_key.entity;
+            let required = self
+                .stable
+                .requirements
+                .get_required(component_key.component_type);
+            for requirement in required {
+                let required_key = ComponentKey::new(entity, requirement.component_type);
+                if self.volatile.components_to_add.contains_key(&required_key)
+                    || self
+                        .stable
+                        .component_mappings
+                        .has_component_no_validation(entity.index, requirement.component_type)
+                {
+                    continue;
+                }
+                trace!("add required component {}", required_key);
+                let causes = self.volatile.components_to_add[&component_key]
+                    .iter()
+                    .map(|it| it.cause.clone())
+                    .to_vec();
+                let data = self
+                    .volatile
+                    .component_data_uncommitted
+                    .get_pool_mut(requirement.component_type)
+                    .add((requirement.constructor)());
+                self.volatile
+                    .components_to_add
+                    .entry(required_key)
+                    .or_default()
+                    .push(ComponentAdd {
+                        data,
+                        cause: Cause::consequence("required_component", causes),
+                    });
+                queue.push(required_key);
+            }
+        }
+    }
+
     pub(crate) fn invoke_disappear_handlers(&mut self, result: &mut ExecutionResult) {
         *result += self.invoke_handlers(ComponentEventType::Disappear);
     }
@@ -103,6 +334,40 @@ impl World {
         *result += self.invoke_handlers(ComponentEventType::Appear);
     }
 
+    pub(crate) fn invoke_modify_handlers(&mut self, result: &mut ExecutionResult) {
+        for (handler, entities) in mem::take(&mut self.volatile.modify_events) {
+            let handler = &self.immutable.modify_handlers[handler];
+            for (entity, causes) in entities {
+                if handler.gated
+                    && !self.immutable.gates.is_open(
+                        handler.name,
+                        handler.module,
+                        &self.stable,
+                        &mut self.volatile,
+                        &mut self.entity_storage,
+                        causes.iter().cloned(),
+                        result,
+                    )
+                {
+                    trace!("skip disabled modify handler {}", handler.name);
+                    continue;
+                }
+                trace!("triggering modify event for {}", entity);
+                *result += invoke_user_code(
+                    &mut self.volatile,
+                    &self.stable,
+                    &mut self.entity_storage,
+                    handler.name,
+                    handler.module,
+                    causes,
+                    [UserCode::new(|ctx| (handler.callback)(ctx, entity.export()))],
+                    |_| {},
+                    &(),
+                );
+            }
+        }
+    }
+
     pub(crate) fn flush_entity_destroy_actions(&mut self) {
         for (entity, _) in mem::take(&mut self.volatile.entities_to_destroy.after_disappear) {
             trace!("flush destroy entity {}", entity);
@@ -111,11 +376,19 @@ impl World {
         }
     }
 
-    pub(crate) fn flush_component_removals(&mut self) {
-        for (component_key, causes) in
-            mem::take(&mut self.volatile.components_to_delete.after_disappear)
-        {
+    pub(crate) fn flush_component_removals(&mut self, result: &mut ExecutionResult) {
+        let removals = mem::take(&mut self.volatile.components_to_delete.after_disappear);
+        if !self.stable.requirements.is_empty() {
+            *result += self.check_required_components_removal(&removals);
+        }
+        for (component_key, causes) in removals {
             trace!("flush remove component {}", component_key);
+            #[cfg(feature = "uuid")]
+            self.stable.unindex_stable_id(component_key);
+            self.stable.indexes.unindex(component_key);
+            self.stable.timers.on_removed(component_key);
+            #[cfg(feature = "spatial")]
+            self.stable.unindex_spatial(component_key);
             let data_key = self
                 .stable
                 .component_mappings
@@ -142,6 +415,48 @@ impl World {
         }
     }
 
+    // required component is removed anyway, but it's reported as an error
+    fn check_required_components_removal(
+        &self,
+        removals: &HashMap<ComponentKey, OptTinyVec<Cause>>,
+    ) -> ExecutionResult {
+        let mut result = ExecutionResult::new();
+        for (component_key, causes) in removals {
+            let entity = component_key.entity;
+            if self
+                .volatile
+                .entities_to_destroy
+                .after_disappear
+                .contains_key(&entity)
+            {
+                continue;
+            }
+            let dependents = self
+                .stable
+                .requirements
+                .get_dependents(component_key.component_type);
+            for dependent in dependents {
+                let remains = self
+                    .stable
+                    .component_mappings
+                    .has_component_no_validation(entity.index, *dependent)
+                    && !removals.contains_key(&ComponentKey::new(entity, *dependent));
+                if !remains {
+                    continue;
+                }
+                let message = format!(
+                    "component {} removed from entity {}, but it's required by {}",
+                    component_key.component_type, entity, dependent
+                );
+                result.errors.push(ExecutionError::violation(
+                    message,
+                    Cause::consequence("remove_required_component", causes.iter().cloned()),
+                ));
+            }
+        }
+        result
+    }
+
     pub(crate) fn generate_disappear_events(&mut self) {
         for (component_key, causes) in
             mem::take(&mut self.volatile.components_to_delete.before_disappear)
@@ -204,12 +519,17 @@ impl World {
                 .signal_managers
                 .get(&signal.payload_type)
                 .unwrap();
+            let payload_type = signal.payload_type;
             *result += manager.invoke(
                 signal,
+                &self.immutable.gates,
                 &mut self.stable,
                 &mut self.volatile,
                 &mut self.entity_storage,
             );
+            if self.stable.timers.tick_signal == Some(payload_type) {
+                *result += self.advance_timers();
+            }
         }
     }
 
@@ -227,22 +547,53 @@ impl World {
         let mut result = ExecutionResult::new();
 
         for filter in filters {
-            if let Some(handlers) = handlers.get(&filter) {
+            let aggregated = self.stable.aggregates.is_aggregated(filter);
+            let handlers = handlers.get(&filter);
+            if handlers.is_some() || aggregated {
+                let filter_key = filter;
                 let filter = &mut self.stable.filter_manager.get_filter_internal(filter);
                 let events = match event_type {
                     ComponentEventType::Appear => &mut filter.appear_events,
                     ComponentEventType::Disappear => &mut filter.disappear_events,
                 };
                 let events = events.as_mut().map(mem::take);
-                for handler in handlers {
+                // handlers see aggregates already updated
+                if let Some(events) = events.as_ref().filter(|_| aggregated) {
+                    let mut aggregates = mem::take(&mut self.stable.aggregates);
+                    match event_type {
+                        ComponentEventType::Appear => {
+                            aggregates.on_appear(filter_key, events.keys(), &self.stable)
+                        }
+                        ComponentEventType::Disappear => {
+                            aggregates.on_disappear(filter_key, events.keys())
+                        }
+                    }
+                    self.stable.aggregates = aggregates;
+                }
+                for handler in handlers.into_iter().flatten() {
                     if let Some(events) = &events {
                         for (entity, causes) in events {
+                            if handler.gated
+                                && !self.immutable.gates.is_open(
+                                    handler.name,
+                                    handler.module,
+                                    &self.stable,
+                                    &mut self.volatile,
+                                    &mut self.entity_storage,
+                                    causes.iter().cloned(),
+                                    &mut result,
+                                )
+                            {
+                                trace!("skip disabled handler {}", handler.name);
+                                continue;
+                            }
                             trace!("triggering event {:?} for {}", event_type, entity);
                             result += invoke_user_code(
                                 &mut self.volatile,
                                 &self.stable,
                                 &mut self.entity_storage,
                                 handler.name,
+                                handler.module,
                                 causes.iter().cloned(),
                                 [UserCode::new(|ctx| {
                                     (handler.callback)(ctx, entity.export())
@@ -2,20 +2,39 @@ use crate::component::ComponentType;
 use crate::component::EcsComponent;
 use crate::entity_key::EntityKey;
 use crate::filter::FilterDesc;
+use crate::internal::aggregate_storage::AggregateStorage;
+use crate::internal::component_indexes::IndexStorage;
 use crate::internal::component_mappings::ComponentMappingStorage;
 use crate::internal::component_pool_manager::ComponentDataKey;
 use crate::internal::component_pool_manager::ComponentPoolManager;
 use crate::internal::component_pool_manager::TempComponentDataKey;
+use crate::internal::component_requirements::ComponentRequirements;
 use crate::internal::entity_storage::EntityStorage;
 use crate::internal::entity_storage::ValidateUncommitted::AllowUncommitted;
 use crate::internal::entity_storage::ValidateUncommitted::DenyUncommitted;
 use crate::internal::filter_manager::FilterManager;
+use crate::internal::world_extras::ComponentValue;
 use crate::internal::world_extras::EntityIndex;
+use crate::internal::world_extras::InternalEntityKey;
 use crate::internal::world_pipeline::PipelineStep;
+#[cfg(feature = "prefab")]
+use crate::prefab::PrefabManager;
+#[cfg(feature = "spatial")]
+use crate::spatial::SpatialIndexStorage;
+#[cfg(feature = "uuid")]
+use crate::stable_id::StableIdIndex;
+use crate::run_condition::ModuleKey;
+use crate::timer::TimerStorage;
 use crate::utils::pool_pump::AbstractPoolPump;
 use crate::utils::pools::SpecificPool;
+use crate::world_result::ComponentError;
+use crate::world_result::QueryError;
 use crate::world_result::WorldResult;
+use std::any::Any;
+use std::any::TypeId;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::panic::RefUnwindSafe;
 
 pub struct StableWorld {
     pub(crate) component_data: ComponentPoolManager<ComponentDataKey>,
@@ -24,8 +43,22 @@ pub struct StableWorld {
     pub(crate) sequence: Vec<PipelineStep>,
     pub(crate) component_data_pumps:
         HashMap<ComponentType, Box<dyn AbstractPoolPump<TempComponentDataKey, ComponentDataKey>>>,
+    pub(crate) entity_key_mappers: HashMap<ComponentType, EntityKeyMapper>,
+    pub(crate) requirements: ComponentRequirements,
+    pub(crate) aggregates: AggregateStorage,
+    pub(crate) indexes: IndexStorage,
+    pub(crate) timers: TimerStorage,
+    pub(crate) module_configs: HashMap<(ModuleKey, TypeId), Box<dyn Any + RefUnwindSafe>>,
+    #[cfg(feature = "prefab")]
+    pub(crate) prefabs: PrefabManager,
+    #[cfg(feature = "uuid")]
+    pub(crate) stable_ids: StableIdIndex,
+    #[cfg(feature = "spatial")]
+    pub(crate) spatial: SpatialIndexStorage,
 }
 
+pub(crate) type EntityKeyMapper = fn(&mut dyn Any, &dyn Fn(EntityKey) -> EntityKey);
+
 impl StableWorld {
     pub(crate) fn new() -> Self {
         Self {
@@ -34,17 +67,87 @@ impl StableWorld {
             filter_manager: Default::default(),
             sequence: vec![],
             component_data_pumps: Default::default(),
+            entity_key_mappers: Default::default(),
+            requirements: Default::default(),
+            aggregates: Default::default(),
+            indexes: Default::default(),
+            timers: Default::default(),
+            module_configs: Default::default(),
+            #[cfg(feature = "prefab")]
+            prefabs: Default::default(),
+            #[cfg(feature = "uuid")]
+            stable_ids: Default::default(),
+            #[cfg(feature = "spatial")]
+            spatial: Default::default(),
         }
     }
 
     pub(crate) fn query(&self, filter: FilterDesc) -> impl Iterator<Item = EntityKey> + '_ {
+        self.get_matched_entities(filter).iter().map(|it| it.export())
+    }
+
+    pub(crate) fn query_internal(
+        &self,
+        filter: FilterDesc,
+    ) -> impl Iterator<Item = InternalEntityKey> + '_ {
+        self.get_matched_entities(filter).iter().copied()
+    }
+
+    pub(crate) fn query_count(&self, filter: FilterDesc) -> usize {
+        self.get_matched_entities(filter).len()
+    }
+
+    pub(crate) fn query_is_empty(&self, filter: FilterDesc) -> bool {
+        self.get_matched_entities(filter).is_empty()
+    }
+
+    pub(crate) fn query_first(&self, filter: FilterDesc) -> Option<EntityKey> {
+        self.query(filter).next()
+    }
+
+    pub(crate) fn query_single(&self, filter: FilterDesc) -> Result<EntityKey, QueryError> {
+        let mut matched = self.query(filter);
+        let first = matched.next().ok_or(QueryError::NotFound)?;
+        if matched.next().is_some() {
+            return Err(QueryError::NotUnique);
+        }
+        Ok(first)
+    }
+
+    pub(crate) fn query_contains(&self, filter: FilterDesc, entity: EntityKey) -> bool {
+        self.get_matched_entities(filter).contains(&entity.inner)
+    }
+
+    fn get_matched_entities(&self, filter: FilterDesc) -> &HashSet<InternalEntityKey> {
         self.filter_manager
             .get_filter(filter)
             .matched_entities
             .as_ref()
             .unwrap_or_else(|| panic!("query is not initialized: {}", filter))
-            .iter()
-            .map(|it| it.export())
+    }
+
+    pub(crate) fn clone_components(
+        &self,
+        entity: EntityIndex,
+    ) -> WorldResult<Vec<ComponentValue>> {
+        let mut components = Vec::new();
+        for (component_type, data_by_entity) in &self.component_mappings.data_by_entity_by_type {
+            let Some(data) = data_by_entity.get(&entity) else {
+                continue;
+            };
+            // copy gets its own identity
+            #[cfg(feature = "uuid")]
+            if *component_type == crate::stable_id::StableId::get_component_type() {
+                continue;
+            }
+            let value = self
+                .component_data
+                .get_pool(*component_type)
+                .and_then(|it| it.clone_any(data))
+                .ok_or(ComponentError::NotClonable(*component_type))?;
+            components.push((*component_type, value));
+        }
+        Ok(components)
     }
 
     pub(crate) fn get_component_mapping_mut(
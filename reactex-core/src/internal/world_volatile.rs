@@ -1,6 +1,10 @@
+use crate::bundle::AbstractPersistedBatch;
+use crate::bundle::EcsBundle;
+use crate::bundle::VolatileBundleTarget;
 use crate::component::EcsComponent;
 use crate::entity_key::EntityKey;
 use crate::internal::cause::Cause;
+use crate::internal::change_buffer::ChangeBuffer;
 use crate::internal::change_buffer::TempEntityKey;
 use crate::internal::component_key::ComponentKey;
 use crate::internal::component_mappings::ComponentMappingStorage;
@@ -18,14 +22,17 @@ use crate::internal::world_extras::ComponentAdd;
 use crate::internal::world_extras::ComponentModify;
 use crate::internal::world_extras::DeleteQueue;
 use crate::internal::world_extras::InternalEntityKey;
+use crate::state::StateTransition;
 use crate::utils::opt_tiny_vec::OptTinyVec;
 use crate::world_result::ComponentError;
 use crate::world_result::WorldError;
 use crate::world_result::WorldResult;
 use crate::ComponentType;
 use log::trace;
+use std::any::type_name;
 use std::any::Any;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 pub struct VolatileWorld {
@@ -34,8 +41,18 @@ pub struct VolatileWorld {
     pub(crate) entities_to_commit: HashMap<InternalEntityKey, OptTinyVec<Cause>>,
     pub(crate) components_to_delete: DeleteQueue<ComponentKey>,
     pub(crate) components_to_add: HashMap<ComponentKey, OptTinyVec<ComponentAdd>>,
-    pub(crate) components_to_modify: HashMap<ComponentKey, OptTinyVec<ComponentModify>>,
+    // spawned batches, their components are added in bulk along with components_to_add
+    pub(crate) batches_to_add: Vec<Box<dyn AbstractPersistedBatch>>,
+    // modifications along with their causes
+    pub(crate) components_to_modify: HashMap<ComponentKey, OptTinyVec<(ComponentModify, Cause)>>,
+    // entities with modified components and causes of the modifications by modify handler index
+    pub(crate) modify_events: BTreeMap<usize, HashMap<InternalEntityKey, OptTinyVec<Cause>>>,
+    // requested transitions of states, applied after exit handlers are invoked
+    pub(crate) state_transitions: Vec<StateTransition>,
+    // states committed by transitions, awaiting enter handlers
+    pub(crate) entered_states: Vec<(ComponentKey, Cause)>,
     pub(crate) component_data_uncommitted: ComponentPoolManager<TempComponentDataKey>,
+    pub(crate) change_buffer: ChangeBuffer,
     pub(crate) current_cause: Cause,
     pub(crate) signal_queue: SignalQueue,
     pub(crate) signal_storage: SignalStorage,
@@ -49,8 +66,13 @@ impl VolatileWorld {
             entities_to_commit: Default::default(),
             components_to_delete: DeleteQueue::new(),
             components_to_add: Default::default(),
+            batches_to_add: Default::default(),
             components_to_modify: Default::default(),
+            modify_events: Default::default(),
+            state_transitions: Default::default(),
+            entered_states: Default::default(),
             component_data_uncommitted: Default::default(),
+            change_buffer: ChangeBuffer::new(),
             current_cause: Cause::initial(),
             signal_queue: Default::default(),
             signal_storage: SignalStorage::new(),
@@ -87,7 +109,43 @@ impl VolatileWorld {
         self.components_to_modify
             .entry(component_key)
             .or_default()
-            .push(ComponentModify { callback });
+            .push((
+                ComponentModify::Callback(callback),
+                self.current_cause.clone(),
+            ));
+    }
+
+    pub(crate) fn set_component<T: EcsComponent>(
+        &mut self,
+        entity: EntityKey,
+        value: T,
+        entity_storage: &EntityStorage,
+    ) -> WorldResult {
+        trace!("set component {}<{}>", entity, T::NAME);
+
+        let entity = entity.validate(entity_storage, DenyUncommitted)?;
+
+        let data = self
+            .change_buffer
+            .component_data
+            .get_pool_mut(T::get_component_type())
+            .specializable_mut()
+            .try_specialize::<T>()
+            .unwrap()
+            .add(value);
+        self.set_component_internal(ComponentKey::new(entity, T::get_component_type()), data);
+        Ok(())
+    }
+
+    pub(crate) fn set_component_internal(
+        &mut self,
+        component_key: ComponentKey,
+        data: TempComponentDataKey,
+    ) {
+        self.components_to_modify
+            .entry(component_key)
+            .or_default()
+            .push((ComponentModify::Set(data), self.current_cause.clone()));
     }
 
     pub(crate) fn add_component<T: EcsComponent>(
@@ -100,6 +158,15 @@ impl VolatileWorld {
 
         let entity = entity.validate(entity_storage, AllowUncommitted)?;
 
+        self.add_component_internal(ComponentKey::new(entity, T::get_component_type()), component);
+        Ok(())
+    }
+
+    pub(crate) fn add_component_internal<T: EcsComponent>(
+        &mut self,
+        component_key: ComponentKey,
+        component: T,
+    ) {
         let data = self
             .component_data_uncommitted
             .get_pool_mut(T::get_component_type())
@@ -109,13 +176,28 @@ impl VolatileWorld {
             .add(component);
 
         self.components_to_add
-            .entry(ComponentKey::new(entity, T::get_component_type()))
+            .entry(component_key)
             .or_default()
             .push(ComponentAdd {
                 data,
                 cause: self.current_cause.clone(),
             });
+    }
+
+    pub(crate) fn add_bundle<T: EcsBundle>(
+        &mut self,
+        entity: EntityKey,
+        bundle: T,
+        entity_storage: &EntityStorage,
+    ) -> WorldResult {
+        trace!("user requested to add bundle {}<{}>", entity, type_name::<T>());
+
+        let entity = entity.validate(entity_storage, AllowUncommitted)?;
 
+        bundle.add_to(&mut VolatileBundleTarget {
+            entity,
+            volatile: self,
+        });
         Ok(())
     }
 
@@ -169,6 +251,18 @@ impl VolatileWorld {
         )
     }
 
+    pub(crate) fn remove_bundle<T: EcsBundle>(
+        &mut self,
+        entity: EntityKey,
+        entity_storage: &EntityStorage,
+        component_mappings: &ComponentMappingStorage,
+    ) -> WorldResult {
+        for component_type in T::COMPONENT_TYPES {
+            self.remove_component_dyn(entity, *component_type, entity_storage, component_mappings)?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn remove_component_internal(
         &mut self,
         component_key: ComponentKey,
@@ -186,6 +280,15 @@ impl VolatileWorld {
         if removed_uncommitted {
             return Ok(());
         }
+        // removal is already in progress, e.g. a disappear handler removes a component of
+        // the entity being destroyed. scheduling it again would repeat disappear events
+        if self
+            .components_to_delete
+            .after_disappear
+            .contains_key(&component_key)
+        {
+            return Ok(());
+        }
         if !component_mappings
             .has_component_no_validation(component_key.entity.index, component_key.component_type)
         {
@@ -208,6 +311,15 @@ impl VolatileWorld {
         sender.signal(payload);
     }
 
+    pub(crate) fn signal_to<T: 'static>(&mut self, target: InternalEntityKey, payload: T) {
+        let mut sender = SignalSender {
+            signal_queue: &mut self.signal_queue,
+            current_cause: &self.current_cause,
+            signal_storage: &mut self.signal_storage,
+        };
+        sender.signal_to(target, payload);
+    }
+
     pub(crate) fn create_entity(
         &mut self,
         entity_storage: &mut EntityStorage,
@@ -227,7 +339,7 @@ impl VolatileWorld {
         &mut self,
         entity: TempEntityKey,
         entity_storage: &mut EntityStorage,
-    ) {
+    ) -> InternalEntityKey {
         trace!("persisting entity {:?}", entity);
         let entity = entity_storage.persist_generated(entity);
         self.entity_component_index.add_entity(entity.index);
@@ -235,6 +347,7 @@ impl VolatileWorld {
             .entry(entity)
             .or_default()
             .push(self.current_cause.clone());
+        entity
     }
 
     pub(crate) fn destroy_entity(
@@ -1,18 +1,33 @@
 #![allow(clippy::new_without_default)]
 
+pub(crate) mod aggregate;
+pub(crate) mod bundle;
 pub(crate) mod component;
 pub(crate) mod container;
 pub(crate) mod ctx;
+pub(crate) mod derived;
 pub(crate) mod entity;
 pub(crate) mod entity_key;
 pub(crate) mod entity_mut;
 pub(crate) mod entity_uncommitted;
 pub(crate) mod facade_2_0;
 pub(crate) mod filter;
+pub(crate) mod index;
 pub(crate) mod internal;
 pub(crate) mod macro_facade;
 pub(crate) mod module;
+#[cfg(feature = "prefab")]
+pub(crate) mod prefab;
+pub(crate) mod run_condition;
+#[cfg(feature = "spatial")]
+pub(crate) mod spatial;
+pub(crate) mod stage;
+#[cfg(feature = "uuid")]
+pub(crate) mod stable_id;
+pub(crate) mod state;
 pub(crate) mod test_facade;
+pub(crate) mod tick_runner;
+pub(crate) mod timer;
 pub(crate) mod utils;
 pub(crate) mod world_result;
 
@@ -20,14 +35,18 @@ pub mod panic_hook;
 pub use ctor;
 pub use reactex_macro::*;
 
+pub use aggregate::EcsAggregate;
+pub use bundle::*;
 pub use component::*;
 pub use container::*;
 pub use ctx::*;
+pub use derived::DeriveFn;
 pub use entity::*;
 pub use entity_key::*;
 pub use entity_mut::*;
 pub use entity_uncommitted::*;
 pub use filter::*;
+pub use index::EcsIndexed;
 pub use internal::cause::Cause;
 pub use internal::execution::ExecutionError;
 pub use internal::execution::ExecutionResult;
@@ -35,5 +54,29 @@ pub use internal::world_configure::ConfigurableWorld;
 pub use internal::world_core::World;
 pub use internal::world_stable::StableWorld;
 pub use internal::world_volatile::VolatileWorld;
+#[doc(hidden)]
+pub use macro_facade::ComponentProbe;
+#[doc(hidden)]
+pub use macro_facade::ComponentProbeFallback;
 pub use module::*;
+#[cfg(feature = "prefab")]
+pub use prefab::Prefab;
+#[cfg(feature = "prefab")]
+pub use prefab::PrefabOverrides;
+pub use run_condition::Switch;
+pub use stage::PostUpdate;
+pub use stage::PreUpdate;
+pub use stage::Stages;
+pub use stage::Update;
+#[cfg(feature = "uuid")]
+pub use stable_id::StableId;
+pub use state::EcsState;
+pub use tick_runner::Clock;
+pub use tick_runner::FixedUpdate;
+pub use tick_runner::ManualClock;
+pub use tick_runner::Render;
+pub use tick_runner::SystemClock;
+pub use tick_runner::TickRunner;
+pub use timer::TimeToLive;
+pub use timer::Timer;
 pub use world_result::*;
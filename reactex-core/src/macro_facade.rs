@@ -1,14 +1,20 @@
 use crate::component::EcsComponent;
+use crate::entity_key::EntityKey;
+use crate::entity_key::MapEntityKeys;
 use crate::filter::FilterDesc;
 use crate::internal::component_pool_manager::ComponentDataKey;
 use crate::internal::component_pool_manager::TempComponentDataKey;
+use crate::internal::component_requirements::RequiredComponent;
 use crate::internal::world_core::COMPONENT_NAMES;
 use crate::internal::world_core::COMPONENT_TYPE_REGISTRATIONS;
 use crate::internal::world_core::QUERIES;
 use crate::utils::pool_pump::SpecificPoolPump;
+use crate::Ctx;
 use crate::World;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::panic::RefUnwindSafe;
 
 impl World {
     pub fn register_component<T: EcsComponent>(&mut self) {
@@ -26,6 +32,10 @@ impl World {
         self.volatile
             .component_data_uncommitted
             .init_pool::<T>("temporary values");
+        self.volatile
+            .change_buffer
+            .component_data
+            .init_pool::<T>("values to set");
 
         self.stable
             .component_data_pumps
@@ -35,6 +45,39 @@ impl World {
             >::default());
     }
 
+    /// Component hook (see `#[ecs(on_add = f)]`), invoked whenever the component
+    /// is added to any entity.
+    pub fn register_on_add<T: EcsComponent>(
+        &mut self,
+        name: &'static str,
+        hook: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        self.add_appear_handler(name, FilterDesc::of::<T>(), false, hook);
+    }
+
+    /// Component hook (see `#[ecs(on_remove = f)]`), invoked whenever the component
+    /// is removed from any entity, including removal on entity destroy.
+    /// The component is still available to the hook.
+    pub fn register_on_remove<T: EcsComponent>(
+        &mut self,
+        name: &'static str,
+        hook: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        self.add_disappear_handler(name, FilterDesc::of::<T>(), false, hook);
+    }
+
+    /// Makes `R` added with its default value along with `T` (see `#[ecs(requires(R))]`),
+    /// unless the entity already has it or gets it at the same transaction.
+    pub fn register_required_component<T: EcsComponent, R: EcsComponent + Default>(&mut self) {
+        self.stable.requirements.add(
+            T::get_component_type(),
+            RequiredComponent {
+                component_type: R::get_component_type(),
+                constructor: || Box::new(R::default()),
+            },
+        );
+    }
+
     pub fn register_type(registration: fn(&mut World)) {
         COMPONENT_TYPE_REGISTRATIONS
             .lock()
@@ -50,3 +93,55 @@ impl World {
             .insert(filter);
     }
 }
+
+// derived components register optional capabilities (clone, deserialization, etc.) only if
+// they implement corresponding traits: inherent method wins over the trait one when its bounds
+// are satisfied
+#[doc(hidden)]
+pub struct ComponentProbe<T>(PhantomData<T>);
+
+#[doc(hidden)]
+pub trait ComponentProbeFallback {
+    fn register_clone(&self, _world: &mut World) {}
+    fn register_entity_key_mapping(&self, _world: &mut World) {}
+    fn register_deserializer(&self, _world: &mut World) {}
+}
+
+impl<T> ComponentProbeFallback for ComponentProbe<T> {}
+
+impl<T> ComponentProbe<T> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: EcsComponent + Clone> ComponentProbe<T> {
+    pub fn register_clone(&self, world: &mut World) {
+        world
+            .stable
+            .component_data
+            .get_pool_mut(T::get_component_type())
+            .specializable_mut()
+            .try_specialize::<T>()
+            .unwrap()
+            .enable_clone();
+    }
+}
+
+impl<T: EcsComponent + MapEntityKeys> ComponentProbe<T> {
+    pub fn register_entity_key_mapping(&self, world: &mut World) {
+        world
+            .stable
+            .entity_key_mappers
+            .insert(T::get_component_type(), |value, map| {
+                value.downcast_mut::<T>().unwrap().map_entity_keys(map)
+            });
+    }
+}
+
+#[cfg(feature = "prefab")]
+impl<T: EcsComponent + serde::de::DeserializeOwned> ComponentProbe<T> {
+    pub fn register_deserializer(&self, world: &mut World) {
+        world.stable.prefabs.register_deserializer::<T>();
+    }
+}
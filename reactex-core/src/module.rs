@@ -1,7 +1,12 @@
 use crate::ConfigurableWorld;
+use crate::Ctx;
+use std::sync::RwLock;
 
 pub struct Module {
+    pub(crate) name: &'static str,
     pub(crate) tasks: Vec<Task>,
+    pub(crate) conditions: Vec<fn(Ctx) -> bool>,
+    pub(crate) dependencies: Vec<&'static RwLock<Module>>,
 }
 
 pub(crate) struct Task {
@@ -9,8 +14,29 @@ pub(crate) struct Task {
 }
 
 impl Module {
-    pub const fn new() -> Module {
-        Module { tasks: vec![] }
+    pub const fn new(name: &'static str) -> Module {
+        Module {
+            name,
+            tasks: vec![],
+            conditions: vec![],
+            dependencies: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Dependencies not added to the container yet are added right before the module.
+    pub fn depends_on(&mut self, module: &'static RwLock<Module>) {
+        self.dependencies.push(module);
+    }
+
+    /// Removes configurators and run conditions, e.g. before reloaded code adds new ones.
+    /// Dependencies are kept.
+    pub fn clear(&mut self) {
+        self.tasks.clear();
+        self.conditions.clear();
     }
 
     pub fn add_configurator(&mut self, action: fn(&mut ConfigurableWorld)) {
@@ -22,7 +48,7 @@ impl Module {
 macro_rules! __ecs_module {
     ($ident:ident) => {
         static $ident: std::sync::RwLock<$crate::Module> =
-            std::sync::RwLock::new($crate::Module::new());
+            std::sync::RwLock::new($crate::Module::new(stringify!($ident)));
     };
 }
 
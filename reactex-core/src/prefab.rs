@@ -0,0 +1,353 @@
+use crate::component::ComponentType;
+use crate::component::EcsComponent;
+use crate::internal::world_extras::ComponentValue;
+use crate::world_result::PrefabError;
+use crate::world_result::WorldResult;
+use crate::ConfigurableWorld;
+use ron::value::RawValue;
+use serde::de::DeserializeOwned;
+use serde::de::Deserializer;
+use serde::de::MapAccess;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::panic::RefUnwindSafe;
+use to_vec::ToVec;
+
+/// Named set of component values. Built in code or loaded from RON/JSON,
+/// where the prefab is a map from component name to its value:
+///
+/// ```ron
+/// {
+///     "goblin": {
+///         "Health": (hp: 10),
+///         "Hostile": (),
+///     },
+/// }
+/// ```
+///
+/// Components are looked up by name as declared in Rust, or by full path if the name is ambiguous.
+/// RON values are deserialized directly, so enums, tuples and non-string map keys are supported.
+/// Overrides are supported for components written as structs.
+#[derive(Default)]
+pub struct Prefab {
+    pub(crate) components: Vec<PrefabComponent>,
+}
+
+pub(crate) enum PrefabComponent {
+    Data {
+        name: &'static str,
+        component_type: ComponentType,
+        value: PrefabData,
+    },
+    Value {
+        name: &'static str,
+        component_type: ComponentType,
+        factory: Box<dyn Fn() -> Box<dyn Any> + RefUnwindSafe>,
+    },
+}
+
+pub(crate) enum PrefabData {
+    Json(Value),
+    Ron(Box<RawValue>),
+}
+
+impl Prefab {
+    pub fn new() -> Prefab {
+        Default::default()
+    }
+
+    pub fn with<T: EcsComponent + Clone>(mut self, value: T) -> Prefab {
+        self.components.push(PrefabComponent::Value {
+            name: T::NAME,
+            component_type: T::get_component_type(),
+            factory: Box::new(move || Box::new(value.clone())),
+        });
+        self
+    }
+}
+
+impl ConfigurableWorld {
+    pub fn add_prefab(&mut self, name: &str, prefab: Prefab) {
+        self.fetus.stable.prefabs.add(name, prefab);
+    }
+
+    pub fn load_prefabs_ron(&mut self, source: &str) -> Result<(), PrefabError> {
+        self.fetus.stable.prefabs.load_ron(source)
+    }
+
+    pub fn load_prefabs_json(&mut self, source: &str) -> Result<(), PrefabError> {
+        self.fetus.stable.prefabs.load_json(source)
+    }
+}
+
+/// Field values replacing ones defined by a prefab, applied before deserialization.
+#[derive(Default)]
+pub struct PrefabOverrides {
+    values: HashMap<String, HashMap<String, OverrideValue>>,
+}
+
+// prefabs may be loaded from either format, so the value is kept in both
+struct OverrideValue {
+    json: Value,
+    ron: Box<RawValue>,
+}
+
+impl PrefabOverrides {
+    pub fn new() -> PrefabOverrides {
+        Default::default()
+    }
+
+    pub fn set(
+        mut self,
+        component: &str,
+        field: &str,
+        value: impl Serialize,
+    ) -> WorldResult<PrefabOverrides> {
+        let invalid = |err: String| PrefabError::InvalidOverride(field.to_owned(), err);
+        let value = OverrideValue {
+            json: serde_json::to_value(&value).map_err(|err| invalid(err.to_string()))?,
+            ron: RawValue::from_rust(&value).map_err(|err| invalid(err.to_string()))?,
+        };
+        self.values
+            .entry(component.to_owned())
+            .or_default()
+            .insert(field.to_owned(), value);
+        Ok(self)
+    }
+}
+
+// the last path segment, generic arguments may contain paths too
+fn short_name(name: &str) -> &str {
+    let path_end = name.find('<').unwrap_or(name.len());
+    let start = name[..path_end].rfind("::").map(|it| it + 2).unwrap_or(0);
+    &name[start..]
+}
+
+pub(crate) struct ComponentDeserializer {
+    name: &'static str,
+    component_type: ComponentType,
+    from_json: fn(Value) -> Result<Box<dyn Any>, String>,
+    from_ron: fn(&RawValue) -> Result<Box<dyn Any>, String>,
+}
+
+impl ComponentDeserializer {
+    fn deserialize(&self, value: &PrefabData) -> Result<Box<dyn Any>, PrefabError> {
+        match value {
+            PrefabData::Json(value) => (self.from_json)(value.clone()),
+            PrefabData::Ron(value) => (self.from_ron)(value),
+        }
+        .map_err(|err| PrefabError::InvalidComponent(self.name.to_owned(), err))
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PrefabManager {
+    deserializers: HashMap<&'static str, ComponentDeserializer>,
+    // None for names declared by several components
+    short_names: HashMap<&'static str, Option<&'static str>>,
+    prefabs: HashMap<String, Prefab>,
+}
+
+impl PrefabManager {
+    pub(crate) fn register_deserializer<T: EcsComponent + DeserializeOwned>(&mut self) {
+        let short_name = short_name(T::NAME);
+        self.short_names
+            .entry(short_name)
+            .and_modify(|it| {
+                if *it != Some(T::NAME) {
+                    *it = None
+                }
+            })
+            .or_insert(Some(T::NAME));
+        self.deserializers.insert(
+            T::NAME,
+            ComponentDeserializer {
+                name: T::NAME,
+                component_type: T::get_component_type(),
+                from_json: |value| match serde_json::from_value::<T>(value) {
+                    Ok(value) => Ok(Box::new(value)),
+                    Err(err) => Err(err.to_string()),
+                },
+                from_ron: |value| match value.into_rust::<T>() {
+                    Ok(value) => Ok(Box::new(value)),
+                    Err(err) => Err(err.to_string()),
+                },
+            },
+        );
+    }
+
+    pub(crate) fn add(&mut self, name: &str, prefab: Prefab) {
+        self.prefabs.insert(name.to_owned(), prefab);
+    }
+
+    pub(crate) fn load_ron(&mut self, source: &str) -> Result<(), PrefabError> {
+        let prefabs: HashMap<String, HashMap<String, Box<RawValue>>> =
+            ron::from_str(source).map_err(|err| PrefabError::Parse(err.to_string()))?;
+        self.load(prefabs, PrefabData::Ron)
+    }
+
+    pub(crate) fn load_json(&mut self, source: &str) -> Result<(), PrefabError> {
+        let prefabs: HashMap<String, Map<String, Value>> =
+            serde_json::from_str(source).map_err(|err| PrefabError::Parse(err.to_string()))?;
+        self.load(prefabs, PrefabData::Json)
+    }
+
+    // everything is validated before the first prefab is added
+    fn load<V>(
+        &mut self,
+        prefabs: HashMap<String, impl IntoIterator<Item = (String, V)>>,
+        data: fn(V) -> PrefabData,
+    ) -> Result<(), PrefabError> {
+        let mut loaded = Vec::new();
+        for (name, components) in prefabs {
+            let mut prefab = Prefab::new();
+            for (component, value) in components {
+                let deserializer = self.get_deserializer(&component)?;
+                let value = data(value);
+                deserializer.deserialize(&value)?;
+                prefab.components.push(PrefabComponent::Data {
+                    name: deserializer.name,
+                    component_type: deserializer.component_type,
+                    value,
+                });
+            }
+            loaded.push((name, prefab));
+        }
+        for (name, prefab) in loaded {
+            self.add(&name, prefab);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn instantiate(
+        &self,
+        name: &str,
+        overrides: &PrefabOverrides,
+    ) -> Result<Vec<ComponentValue>, PrefabError> {
+        let prefab = self
+            .prefabs
+            .get(name)
+            .ok_or_else(|| PrefabError::NotFound(name.to_owned()))?;
+
+        let mut overrides_by_name = HashMap::new();
+        for (component, fields) in &overrides.values {
+            let name = self.get_deserializer(component)?.name;
+            overrides_by_name.insert(name, fields);
+        }
+
+        let mut components = Vec::new();
+        for component in &prefab.components {
+            match component {
+                PrefabComponent::Data {
+                    name,
+                    component_type,
+                    value,
+                } => {
+                    let deserializer = &self.deserializers[name];
+                    let value = match overrides_by_name.remove(name) {
+                        None => deserializer.deserialize(value)?,
+                        Some(fields) => {
+                            let value = override_fields(value, fields).ok_or_else(|| {
+                                PrefabError::OverrideNotSupported(name.to_string())
+                            })?;
+                            deserializer.deserialize(&value)?
+                        }
+                    };
+                    components.push((*component_type, value));
+                }
+                PrefabComponent::Value {
+                    name,
+                    component_type,
+                    factory,
+                } => {
+                    if overrides_by_name.contains_key(name) {
+                        return Err(PrefabError::OverrideNotSupported(name.to_string()));
+                    }
+                    components.push((*component_type, factory()));
+                }
+            }
+        }
+        if let Some(name) = overrides_by_name.keys().next() {
+            return Err(PrefabError::MissingComponent(name.to_string()));
+        }
+        Ok(components)
+    }
+
+    fn get_deserializer(&self, name: &str) -> Result<&ComponentDeserializer, PrefabError> {
+        let full_name = match self.short_names.get(name) {
+            Some(Some(full_name)) => full_name,
+            Some(None) => return Err(PrefabError::AmbiguousComponent(name.to_owned())),
+            None => name,
+        };
+        self.deserializers
+            .get(full_name)
+            .ok_or_else(|| PrefabError::UnknownComponent(name.to_owned()))
+    }
+}
+
+// `None` if the value isn't written as a struct
+fn override_fields(
+    value: &PrefabData,
+    fields: &HashMap<String, OverrideValue>,
+) -> Option<PrefabData> {
+    match value {
+        PrefabData::Json(value) => {
+            let Value::Object(object) = value else {
+                return None;
+            };
+            let mut object = object.clone();
+            for (field, value) in fields {
+                object.insert(field.clone(), value.json.clone());
+            }
+            Some(PrefabData::Json(Value::Object(object)))
+        }
+        PrefabData::Ron(value) => {
+            // struct name is optional in RON, so the struct is written anew without it
+            let RonStruct(mut object) = value.into_rust().ok()?;
+            for (field, value) in fields {
+                object.insert(field.clone(), value.ron.clone());
+            }
+            let fields = object
+                .iter()
+                .map(|(field, value)| format!("{}: {}", field, value.get_ron()))
+                .to_vec();
+            let ron = format!("({})", fields.join(", "));
+            Some(PrefabData::Ron(RawValue::from_boxed_ron(ron.into()).ok()?))
+        }
+    }
+}
+
+// fields of a RON struct, with or without its name
+struct RonStruct(HashMap<String, Box<RawValue>>);
+
+impl<'de> Deserialize<'de> for RonStruct {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = RonStruct;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                write!(formatter, "struct")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RonStruct, A::Error> {
+                let mut fields = HashMap::new();
+                while let Some(field) = map.next_key::<String>()? {
+                    fields.insert(field, map.next_value()?);
+                }
+                Ok(RonStruct(fields))
+            }
+        }
+
+        // struct syntax is a map only for `deserialize_any`
+        deserializer.deserialize_any(FieldsVisitor)
+    }
+}
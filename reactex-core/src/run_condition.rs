@@ -0,0 +1,214 @@
+use crate::internal::cause::Cause;
+use crate::internal::entity_storage::EntityStorage;
+use crate::internal::execution::invoke_user_code;
+use crate::internal::execution::ExecutionResult;
+use crate::internal::execution::UserCode;
+use crate::internal::world_stable::StableWorld;
+use crate::internal::world_volatile::VolatileWorld;
+use crate::module::Module;
+use crate::ConfigurableWorld;
+use crate::Ctx;
+use crate::EcsContainer;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::panic::RefUnwindSafe;
+use std::rc::Rc;
+use std::sync::RwLock;
+use to_vec::ToVec;
+
+pub(crate) type RunCondition = dyn Fn(Ctx) -> bool + RefUnwindSafe;
+
+// address of the module static
+pub(crate) type ModuleKey = usize;
+
+pub(crate) fn module_key(module: &RwLock<Module>) -> ModuleKey {
+    module as *const RwLock<Module> as ModuleKey
+}
+
+/// Runtime on/off flag of a module or a handler, clones toggle the same flag.
+#[derive(Clone)]
+pub struct Switch {
+    enabled: Rc<Cell<bool>>,
+}
+
+impl Default for Switch {
+    fn default() -> Self {
+        Switch {
+            enabled: Rc::new(Cell::new(true)),
+        }
+    }
+}
+
+impl Switch {
+    pub fn enable(&self) {
+        self.enabled.set(true);
+    }
+
+    pub fn disable(&self) {
+        self.enabled.set(false);
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+}
+
+#[derive(Default)]
+struct Gate {
+    switch: Switch,
+    conditions: Vec<Box<RunCondition>>,
+}
+
+// handler name within its module, `None` for handlers registered outside of modules
+type HandlerKey = (Option<ModuleKey>, &'static str);
+
+// handlers of a module with the same name share the gate, e.g. the ones generated for
+// different signals
+#[derive(Default)]
+pub(crate) struct Gates {
+    handlers: HashMap<HandlerKey, Gate>,
+    modules: HashMap<ModuleKey, Gate>,
+    // module being added, its handlers are bound to it
+    pub(crate) configured_module: Option<ModuleKey>,
+}
+
+impl Gates {
+    // ungated handlers get no switch, while they are still removed along with the module
+    pub(crate) fn register_handler(
+        &mut self,
+        name: &'static str,
+        gated: bool,
+    ) -> Option<ModuleKey> {
+        if gated {
+            self.handlers
+                .entry((self.configured_module, name))
+                .or_default();
+        }
+        self.configured_module
+    }
+
+    pub(crate) fn register_module(&mut self, key: ModuleKey, conditions: &[fn(Ctx) -> bool]) {
+        let gate = self.modules.entry(key).or_default();
+        for condition in conditions {
+            gate.conditions.push(Box::new(*condition));
+        }
+    }
+
+    // switches survive reloads, while conditions are registered again
+    pub(crate) fn reset_module(&mut self, key: ModuleKey) {
+        if let Some(gate) = self.modules.get_mut(&key) {
+            gate.conditions.clear();
+        }
+        for ((module, _), gate) in &mut self.handlers {
+            if *module == Some(key) {
+                gate.conditions.clear();
+            }
+        }
+    }
+
+    pub(crate) fn remove_module(&mut self, key: ModuleKey) {
+        self.modules.remove(&key);
+        self.handlers.retain(|(module, _), _| *module != Some(key));
+    }
+
+    // a handler runs if both it and its module are enabled and all their run conditions hold,
+    // a panicking condition is reported and counts as not holding
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn is_open(
+        &self,
+        handler: &'static str,
+        module: Option<ModuleKey>,
+        stable: &StableWorld,
+        volatile: &mut VolatileWorld,
+        entity_storage: &mut EntityStorage,
+        causes: impl IntoIterator<Item = Cause>,
+        result: &mut ExecutionResult,
+    ) -> bool {
+        let gates = [
+            self.handlers.get(&(module, handler)),
+            module.and_then(|it| self.modules.get(&it)),
+        ];
+        let gates = gates.into_iter().flatten();
+        if !gates.clone().all(|it| it.switch.is_enabled()) {
+            return false;
+        }
+        let conditions = gates.flat_map(|it| it.conditions.iter()).to_vec();
+        if conditions.is_empty() {
+            return true;
+        }
+        let mut open = false;
+        *result += invoke_user_code(
+            volatile,
+            stable,
+            entity_storage,
+            handler,
+            module,
+            causes,
+            [UserCode::new(|ctx| conditions.iter().all(|it| it(ctx)))],
+            |it| open = it,
+            &(),
+        );
+        open
+    }
+}
+
+impl Module {
+    /// Runs handlers of the module only while the condition holds. It's evaluated before each
+    /// dispatch of a signal or an event to a handler.
+    pub fn run_if(&mut self, condition: fn(Ctx) -> bool) {
+        self.conditions.push(condition);
+    }
+}
+
+impl ConfigurableWorld {
+    /// Runs the handler only while the condition holds. Refers to the handler of the module being
+    /// configured, if any.
+    pub fn run_if(
+        &mut self,
+        handler: &'static str,
+        condition: impl Fn(Ctx) -> bool + RefUnwindSafe + 'static,
+    ) {
+        self.fetus
+            .immutable
+            .gates
+            .handlers
+            .entry((self.fetus.immutable.gates.configured_module, handler))
+            .or_default()
+            .conditions
+            .push(Box::new(condition));
+    }
+}
+
+impl EcsContainer {
+    /// Handle to enable or disable handlers of the module, `None` if it isn't added.
+    pub fn module_switch(&self, module: &RwLock<Module>) -> Option<Switch> {
+        let gate = self
+            .world
+            .immutable
+            .gates
+            .modules
+            .get(&module_key(module))?;
+        Some(gate.switch.clone())
+    }
+
+    /// Handle to enable or disable handlers with the name registered outside of modules, `None`
+    /// if there are none.
+    pub fn handler_switch(&self, name: &str) -> Option<Switch> {
+        self.find_handler_switch(None, name)
+    }
+
+    /// Handle to enable or disable handlers of the module with the name, `None` if there are none.
+    pub fn module_handler_switch(&self, module: &RwLock<Module>, name: &str) -> Option<Switch> {
+        self.find_handler_switch(Some(module_key(module)), name)
+    }
+
+    fn find_handler_switch(&self, module: Option<ModuleKey>, name: &str) -> Option<Switch> {
+        let gates = &self.world.immutable.gates.handlers;
+        let (_, gate) = gates.iter().find(|(key, _)| **key == (module, name))?;
+        Some(gate.switch.clone())
+    }
+}
@@ -0,0 +1,354 @@
+use crate::component::ComponentType;
+use crate::component::EcsComponent;
+use crate::filter::FilterDesc;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::run_condition::ModuleKey;
+use crate::StableWorld;
+use crate::World;
+use std::any::Any;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::mem;
+use std::panic::RefUnwindSafe;
+
+impl World {
+    /// Indexes entities by a 2D/3D position extracted from their component `C`,
+    /// they are looked up with `Ctx::query_radius` and `Ctx::query_aabb`.
+    /// Only a single spatial index per number of dimensions is allowed.
+    pub fn register_spatial_index<C: EcsComponent, const N: usize>(
+        &mut self,
+        cell_size: f32,
+        position: impl Fn(&C) -> [f32; N] + RefUnwindSafe + 'static,
+    ) {
+        assert!(cell_size > 0.0, "cell size should be positive");
+        let component_type = C::get_component_type();
+        self.stable.spatial.add(
+            component_type,
+            N,
+            Box::new(SpatialIndex {
+                position: Box::new(position),
+                grid: SpatialGrid {
+                    cell_size,
+                    cells: Default::default(),
+                    positions: Default::default(),
+                },
+            }),
+            self.immutable.gates.configured_module,
+        );
+        for entity in self.existing_entities(&[component_type]) {
+            self.stable
+                .index_spatial(ComponentKey::new(entity, component_type));
+        }
+    }
+}
+
+// entities are bucketed by cells of the uniform grid, only occupied cells are stored
+pub(crate) struct SpatialGrid<const N: usize> {
+    cell_size: f32,
+    cells: HashMap<[i32; N], HashSet<InternalEntityKey>>,
+    positions: HashMap<InternalEntityKey, [f32; N]>,
+}
+
+impl<const N: usize> SpatialGrid<N> {
+    fn cell(&self, point: [f32; N]) -> [i32; N] {
+        point.map(|it| (it / self.cell_size).floor() as i32)
+    }
+
+    fn insert(&mut self, entity: InternalEntityKey, point: [f32; N]) {
+        self.remove(entity);
+        let cell = self.cell(point);
+        self.cells.entry(cell).or_default().insert(entity);
+        self.positions.insert(entity, point);
+    }
+
+    fn remove(&mut self, entity: InternalEntityKey) {
+        let Some(point) = self.positions.remove(&entity) else {
+            return;
+        };
+        let cell = self.cell(point);
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    fn query_aabb(&self, min: [f32; N], max: [f32; N]) -> Vec<InternalEntityKey> {
+        let min_cell = self.cell(min);
+        let max_cell = self.cell(max);
+        let inside = |point: &[f32; N]| (0..N).all(|i| min[i] <= point[i] && point[i] <= max[i]);
+        let mut result = Vec::new();
+        let mut visit = |entities: &HashSet<InternalEntityKey>| {
+            for entity in entities {
+                if inside(&self.positions[entity]) {
+                    result.push(*entity);
+                }
+            }
+        };
+        let range_size = (0..N)
+            .map(|i| (max_cell[i] as i64 - min_cell[i] as i64 + 1).max(0) as u64)
+            .try_fold(1u64, |acc, it| acc.checked_mul(it))
+            .unwrap_or(u64::MAX);
+        if range_size > self.cells.len() as u64 {
+            // the box is large compared to the occupied area
+            for (cell, entities) in &self.cells {
+                if (0..N).all(|i| min_cell[i] <= cell[i] && cell[i] <= max_cell[i]) {
+                    visit(entities);
+                }
+            }
+            return result;
+        }
+        let mut cell = min_cell;
+        'cells: loop {
+            if let Some(entities) = self.cells.get(&cell) {
+                visit(entities);
+            }
+            for i in 0..N {
+                if cell[i] < max_cell[i] {
+                    cell[i] += 1;
+                    continue 'cells;
+                }
+                cell[i] = min_cell[i];
+            }
+            return result;
+        }
+    }
+
+    fn query_radius(&self, center: [f32; N], radius: f32) -> Vec<InternalEntityKey> {
+        let min = center.map(|it| it - radius);
+        let max = center.map(|it| it + radius);
+        let mut result = self.query_aabb(min, max);
+        result.retain(|entity| {
+            let point = &self.positions[entity];
+            let distance_squared: f32 = (0..N).map(|i| (point[i] - center[i]).powi(2)).sum();
+            distance_squared <= radius * radius
+        });
+        result
+    }
+}
+
+pub(crate) struct SpatialIndex<C, const N: usize> {
+    position: PositionExtractor<C, N>,
+    grid: SpatialGrid<N>,
+}
+
+type PositionExtractor<C, const N: usize> = Box<dyn Fn(&C) -> [f32; N] + RefUnwindSafe>;
+
+pub(crate) trait AbstractSpatialIndex: RefUnwindSafe {
+    fn grid(&self) -> &dyn Any;
+
+    fn index(&mut self, entity: InternalEntityKey, stable: &StableWorld);
+
+    fn unindex(&mut self, entity: InternalEntityKey);
+
+    fn shrink_to_fit(&mut self);
+}
+
+impl<C: EcsComponent, const N: usize> AbstractSpatialIndex for SpatialIndex<C, N> {
+    fn grid(&self) -> &dyn Any {
+        &self.grid
+    }
+
+    fn index(&mut self, entity: InternalEntityKey, stable: &StableWorld) {
+        match stable.get_component_no_validation::<C>(entity.index) {
+            Some(component) => self.grid.insert(entity, (self.position)(component)),
+            None => self.grid.remove(entity),
+        }
+    }
+
+    fn unindex(&mut self, entity: InternalEntityKey) {
+        self.grid.remove(entity);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.grid.cells.shrink_to_fit();
+        self.grid.positions.shrink_to_fit();
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SpatialIndexStorage {
+    by_component_type: HashMap<ComponentType, Box<dyn AbstractSpatialIndex>>,
+    by_dimensions: HashMap<usize, ComponentType>,
+    by_module: HashMap<ModuleKey, Vec<ComponentType>>,
+}
+
+impl SpatialIndexStorage {
+    fn add(
+        &mut self,
+        component_type: ComponentType,
+        dimensions: usize,
+        index: Box<dyn AbstractSpatialIndex>,
+        module: Option<ModuleKey>,
+    ) {
+        let previous = self.by_dimensions.insert(dimensions, component_type);
+        assert!(
+            previous.is_none(),
+            "spatial index of {} dimensions is registered twice",
+            dimensions
+        );
+        let previous = self.by_component_type.insert(component_type, index);
+        assert!(
+            previous.is_none(),
+            "component is spatially indexed twice: {}",
+            component_type
+        );
+        if let Some(module) = module {
+            self.by_module
+                .entry(module)
+                .or_default()
+                .push(component_type);
+        }
+    }
+
+    pub(crate) fn remove_module(&mut self, module: ModuleKey) {
+        let removed = self.by_module.remove(&module).unwrap_or_default();
+        for component_type in &removed {
+            self.by_component_type.remove(component_type);
+        }
+        self.by_dimensions.retain(|_, it| !removed.contains(it));
+    }
+
+    fn get<const N: usize>(&self) -> &SpatialGrid<N> {
+        let component_type = self
+            .by_dimensions
+            .get(&N)
+            .unwrap_or_else(|| panic!("spatial index of {} dimensions is not registered", N));
+        self.by_component_type[component_type]
+            .grid()
+            .downcast_ref()
+            .unwrap()
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for index in self.by_component_type.values_mut() {
+            index.shrink_to_fit();
+        }
+    }
+}
+
+impl StableWorld {
+    pub(crate) fn index_spatial(&mut self, component_key: ComponentKey) {
+        if !self
+            .spatial
+            .by_component_type
+            .contains_key(&component_key.component_type)
+        {
+            return;
+        }
+        let mut spatial = mem::take(&mut self.spatial);
+        spatial
+            .by_component_type
+            .get_mut(&component_key.component_type)
+            .unwrap()
+            .index(component_key.entity, self);
+        self.spatial = spatial;
+    }
+
+    pub(crate) fn unindex_spatial(&mut self, component_key: ComponentKey) {
+        if let Some(index) = self
+            .spatial
+            .by_component_type
+            .get_mut(&component_key.component_type)
+        {
+            index.unindex(component_key.entity);
+        }
+    }
+
+    pub(crate) fn query_aabb<const N: usize>(
+        &self,
+        min: [f32; N],
+        max: [f32; N],
+        filter: FilterDesc,
+    ) -> Vec<InternalEntityKey> {
+        let mut result = self.spatial.get::<N>().query_aabb(min, max);
+        result.retain(|it| self.query_contains(filter, it.export()));
+        result
+    }
+
+    pub(crate) fn query_radius<const N: usize>(
+        &self,
+        center: [f32; N],
+        radius: f32,
+        filter: FilterDesc,
+    ) -> Vec<InternalEntityKey> {
+        let mut result = self.spatial.get::<N>().query_radius(center, radius);
+        result.retain(|it| self.query_contains(filter, it.export()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::world_extras::EntityGeneration;
+    use crate::internal::world_extras::EntityIndex;
+
+    fn entity(index: u32) -> InternalEntityKey {
+        InternalEntityKey {
+            index: EntityIndex { index },
+            generation: EntityGeneration(0),
+            temp: false,
+        }
+    }
+
+    fn grid() -> SpatialGrid<2> {
+        SpatialGrid {
+            cell_size: 10.0,
+            cells: Default::default(),
+            positions: Default::default(),
+        }
+    }
+
+    fn sorted(mut entities: Vec<InternalEntityKey>) -> Vec<InternalEntityKey> {
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn points_found_across_cells() {
+        let mut grid = grid();
+        grid.insert(entity(0), [1.0, 1.0]);
+        grid.insert(entity(1), [-1.0, -1.0]);
+        grid.insert(entity(2), [25.0, 1.0]);
+
+        assert_eq!(
+            sorted(grid.query_radius([0.0, 0.0], 2.0)),
+            vec![entity(0), entity(1)]
+        );
+        assert_eq!(
+            sorted(grid.query_aabb([0.0, 0.0], [30.0, 5.0])),
+            vec![entity(0), entity(2)]
+        );
+        assert!(grid.query_radius([0.0, 0.0], 1.0).is_empty());
+    }
+
+    #[test]
+    fn moved_point_leaves_previous_cell() {
+        let mut grid = grid();
+        grid.insert(entity(0), [1.0, 1.0]);
+        grid.insert(entity(0), [55.0, 55.0]);
+
+        assert!(grid.query_radius([1.0, 1.0], 1.0).is_empty());
+        assert_eq!(grid.query_radius([55.0, 55.0], 1.0), vec![entity(0)]);
+        assert_eq!(grid.cells.len(), 1);
+
+        grid.remove(entity(0));
+
+        assert!(grid.cells.is_empty());
+        assert!(grid.positions.is_empty());
+    }
+
+    #[test]
+    fn huge_box_scans_occupied_cells() {
+        let mut grid = grid();
+        grid.insert(entity(0), [1.0e6, -1.0e6]);
+
+        assert_eq!(
+            grid.query_aabb([-1.0e9, -1.0e9], [1.0e9, 1.0e9]),
+            vec![entity(0)]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::component::EcsComponent;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::StableWorld;
+use crate::World;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Identity of an entity which survives save/load and is meaningful across processes,
+/// unlike `EntityKey`. Entities are looked up by it with `Ctx::find_by_stable_id`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StableId(pub Uuid);
+
+impl StableId {
+    pub fn new_random() -> StableId {
+        StableId(Uuid::new_v4())
+    }
+}
+
+// built-in component, so its index is reserved instead of being generated by derive
+impl EcsComponent for StableId {
+    const INDEX: u16 = u16::MAX;
+    const NAME: &'static str = concat!(module_path!(), "::StableId");
+}
+
+#[ctor::ctor]
+fn register_type_callback_stable_id() {
+    World::register_type(|world| world.register_component::<StableId>());
+}
+
+#[derive(Default)]
+pub(crate) struct StableIdIndex {
+    entities: HashMap<Uuid, InternalEntityKey>,
+}
+
+impl StableIdIndex {
+    pub(crate) fn get(&self, id: StableId) -> Option<InternalEntityKey> {
+        self.entities.get(&id.0).copied()
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.entities.shrink_to_fit();
+    }
+}
+
+impl StableWorld {
+    pub(crate) fn index_stable_id(&mut self, component_key: ComponentKey) {
+        if component_key.component_type != StableId::get_component_type() {
+            return;
+        }
+        let entity = component_key.entity;
+        if let Some(&id) = self.get_component_no_validation::<StableId>(entity.index) {
+            self.stable_ids.entities.insert(id.0, entity);
+        }
+    }
+
+    pub(crate) fn unindex_stable_id(&mut self, component_key: ComponentKey) {
+        if component_key.component_type != StableId::get_component_type() {
+            return;
+        }
+        let entity = component_key.entity;
+        if let Some(&id) = self.get_component_no_validation::<StableId>(entity.index) {
+            // the same id may be reassigned to another entity in the meantime
+            if self.stable_ids.entities.get(&id.0) == Some(&entity) {
+                self.stable_ids.entities.remove(&id.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::internal::execution::ExecutionResult;
+use crate::EcsContainer;
+use log::trace;
+use std::any::type_name;
+use std::any::TypeId;
+use std::panic::UnwindSafe;
+
+/// The first of the default stages.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PreUpdate;
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Update;
+
+/// The last of the default stages.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PostUpdate;
+
+/// Ordered signals sent by `EcsContainer::tick`, `PreUpdate`, `Update` and `PostUpdate`
+/// by default. Any signal type constructed with `Default` can be a stage.
+pub struct Stages {
+    stages: Vec<Stage>,
+}
+
+struct Stage {
+    type_id: TypeId,
+    name: &'static str,
+    run: fn(&mut EcsContainer) -> ExecutionResult,
+}
+
+impl Default for Stages {
+    fn default() -> Self {
+        let mut stages = Stages::empty();
+        stages.push::<PreUpdate>();
+        stages.push::<Update>();
+        stages.push::<PostUpdate>();
+        stages
+    }
+}
+
+impl Stages {
+    pub fn empty() -> Stages {
+        Stages { stages: vec![] }
+    }
+
+    pub fn push<T: Default + UnwindSafe + 'static>(&mut self) {
+        let index = self.stages.len();
+        self.insert::<T>(index);
+    }
+
+    pub fn insert_before<T: Default + UnwindSafe + 'static, Before: 'static>(&mut self) {
+        let index = self.position::<Before>();
+        self.insert::<T>(index);
+    }
+
+    pub fn insert_after<T: Default + UnwindSafe + 'static, After: 'static>(&mut self) {
+        let index = self.position::<After>() + 1;
+        self.insert::<T>(index);
+    }
+
+    pub fn remove<T: 'static>(&mut self) {
+        let index = self.position::<T>();
+        self.stages.remove(index);
+    }
+
+    fn insert<T: Default + UnwindSafe + 'static>(&mut self, index: usize) {
+        assert!(
+            !self.contains::<T>(),
+            "stage {} is added twice",
+            type_name::<T>()
+        );
+        self.stages.insert(
+            index,
+            Stage {
+                type_id: TypeId::of::<T>(),
+                name: type_name::<T>(),
+                run: run_stage::<T>,
+            },
+        );
+    }
+
+    fn contains<T: 'static>(&self) -> bool {
+        self.stages.iter().any(|it| it.type_id == TypeId::of::<T>())
+    }
+
+    fn position<T: 'static>(&self) -> usize {
+        self.stages
+            .iter()
+            .position(|it| it.type_id == TypeId::of::<T>())
+            .unwrap_or_else(|| panic!("stage {} is not found", type_name::<T>()))
+    }
+}
+
+// every stage is a separate transaction, so the pipeline is drained before the next one
+fn run_stage<T: Default + UnwindSafe + 'static>(ecs: &mut EcsContainer) -> ExecutionResult {
+    if !ecs.has_signal_handlers::<T>() {
+        return ExecutionResult::new();
+    }
+    let (_, result) = ecs.execute_once(type_name::<T>(), |ctx| ctx.send_signal(T::default()));
+    result
+}
+
+impl EcsContainer {
+    /// Sends the stage signals in order, stages without handlers are skipped.
+    pub fn tick(&mut self) -> ExecutionResult {
+        let mut result = ExecutionResult::new();
+        for index in 0..self.stages.stages.len() {
+            let stage = &self.stages.stages[index];
+            trace!("run stage {}", stage.name);
+            let run = stage.run;
+            result += run(self);
+        }
+        result
+    }
+}
@@ -0,0 +1,267 @@
+use crate::component::EcsComponent;
+use crate::entity_key::EntityKey;
+use crate::filter::FilterDesc;
+use crate::internal::cause::Cause;
+use crate::internal::change_buffer::ComponentModification;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::entity_storage::ValidateUncommitted::DenyUncommitted;
+use crate::internal::execution::invoke_user_code;
+use crate::internal::execution::UserCode;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::run_condition::ModuleKey;
+use crate::Ctx;
+use crate::ExecutionResult;
+use crate::StableWorld;
+use crate::VolatileWorld;
+use crate::World;
+use log::trace;
+use std::collections::HashSet;
+use std::mem;
+use std::panic::RefUnwindSafe;
+use std::rc::Rc;
+use to_vec::ToVec;
+
+/// Enum component, which variants are states of a finite state machine.
+/// Implemented with `#[derive(EcsState)]` instead of `#[derive(EcsComponent)]`.
+pub trait EcsState: EcsComponent {}
+
+pub(crate) struct StateHandler {
+    name: &'static str,
+    module: Option<ModuleKey>,
+    filter: FilterDesc,
+    matches: StateMatcher,
+    callback: Rc<dyn Fn(Ctx, EntityKey) + RefUnwindSafe>,
+}
+
+type StateMatcher = Box<dyn Fn(&StableWorld, InternalEntityKey) -> bool + RefUnwindSafe>;
+
+#[derive(Default)]
+pub(crate) struct StateHandlers {
+    enter: Vec<StateHandler>,
+    exit: Vec<StateHandler>,
+}
+
+impl StateHandlers {
+    // returns filters of the removed handlers, one per handler
+    pub(crate) fn remove_module_handlers(&mut self, module: ModuleKey) -> Vec<FilterDesc> {
+        let mut filters = vec![];
+        for handlers in [&mut self.enter, &mut self.exit] {
+            handlers.retain(|it| {
+                let removed = it.module == Some(module);
+                if removed {
+                    filters.push(it.filter);
+                }
+                !removed
+            });
+        }
+        filters
+    }
+}
+
+pub(crate) struct StateTransition {
+    component_key: ComponentKey,
+    assign: ComponentModification,
+    cause: Cause,
+}
+
+#[derive(Copy, Clone)]
+enum StateEvent {
+    Enter,
+    Exit,
+}
+
+impl World {
+    /// Invoked when entity matching the filter enters the state, either by transition or by
+    /// appearance with the state already set. The filter should contain the state component.
+    pub(crate) fn add_state_enter_handler<S: EcsState>(
+        &mut self,
+        name: &'static str,
+        filter: FilterDesc,
+        state: fn(&S) -> bool,
+        callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        self.add_state_handler(StateEvent::Enter, name, filter, state, callback);
+    }
+
+    /// Invoked when entity matching the filter exits the state, either by transition or by
+    /// disappearance in the state. The filter should contain the state component.
+    pub(crate) fn add_state_exit_handler<S: EcsState>(
+        &mut self,
+        name: &'static str,
+        filter: FilterDesc,
+        state: fn(&S) -> bool,
+        callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        self.add_state_handler(StateEvent::Exit, name, filter, state, callback);
+    }
+
+    fn add_state_handler<S: EcsState>(
+        &mut self,
+        event: StateEvent,
+        name: &'static str,
+        filter: FilterDesc,
+        state: fn(&S) -> bool,
+        callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        assert!(
+            filter.component_types.contains(&S::get_component_type()),
+            "filter of state handler {} should contain {}",
+            name,
+            S::NAME
+        );
+        let callback: Rc<dyn Fn(Ctx, EntityKey) + RefUnwindSafe> = Rc::new(callback);
+        let appearance = callback.clone();
+        let wrapper = move |ctx: Ctx, entity: EntityKey| {
+            let in_state = ctx
+                .get_entity(entity)
+                .is_some_and(|it| it.get::<S>().is_some_and(state));
+            if in_state {
+                appearance(ctx, entity);
+            }
+        };
+        match event {
+            StateEvent::Enter => self.add_appear_handler(name, filter, true, wrapper),
+            StateEvent::Exit => self.add_disappear_handler(name, filter, true, wrapper),
+        }
+        self.stable
+            .filter_manager
+            .get_filter_mut(filter)
+            .track_matched_entities(&self.entity_storage, &self.stable.component_mappings);
+
+        let handlers = self
+            .immutable
+            .state_handlers
+            .entry(S::get_component_type())
+            .or_default();
+        let handler = StateHandler {
+            name,
+            module: self.immutable.gates.configured_module,
+            filter,
+            matches: Box::new(move |stable, entity| {
+                stable
+                    .get_component_no_validation::<S>(entity.index)
+                    .is_some_and(state)
+            }),
+            callback,
+        };
+        match event {
+            StateEvent::Enter => handlers.enter.push(handler),
+            StateEvent::Exit => handlers.exit.push(handler),
+        }
+    }
+
+    // exit handlers see the previous state, then the new one is committed with other modifications
+    pub(crate) fn invoke_exit_handlers(&mut self, result: &mut ExecutionResult) {
+        for transition in last_transitions(mem::take(&mut self.volatile.state_transitions)) {
+            let component_key = transition.component_key;
+            let entity = component_key.entity;
+            let committed = entity
+                .export()
+                .validate(&self.entity_storage, DenyUncommitted)
+                .is_ok()
+                && self
+                    .stable
+                    .component_mappings
+                    .has_component_no_validation(entity.index, component_key.component_type);
+            if !committed {
+                trace!("skip transition of missing state {}", component_key);
+                continue;
+            }
+            *result +=
+                self.invoke_state_handlers(StateEvent::Exit, component_key, &transition.cause);
+            self.volatile
+                .modify_component_internal(component_key, transition.assign);
+            self.volatile
+                .entered_states
+                .push((component_key, transition.cause));
+        }
+    }
+
+    pub(crate) fn invoke_enter_handlers(&mut self, result: &mut ExecutionResult) {
+        for (component_key, cause) in mem::take(&mut self.volatile.entered_states) {
+            *result += self.invoke_state_handlers(StateEvent::Enter, component_key, &cause);
+        }
+    }
+
+    fn invoke_state_handlers(
+        &mut self,
+        event: StateEvent,
+        component_key: ComponentKey,
+        cause: &Cause,
+    ) -> ExecutionResult {
+        let mut result = ExecutionResult::new();
+        let Some(handlers) = self
+            .immutable
+            .state_handlers
+            .get(&component_key.component_type)
+        else {
+            return result;
+        };
+        let handlers = match event {
+            StateEvent::Enter => &handlers.enter,
+            StateEvent::Exit => &handlers.exit,
+        };
+        let entity = component_key.entity;
+        for handler in handlers {
+            let matched = (handler.matches)(&self.stable, entity)
+                && self.stable.query_contains(handler.filter, entity.export());
+            if !matched {
+                continue;
+            }
+            if !self.immutable.gates.is_open(
+                handler.name,
+                handler.module,
+                &self.stable,
+                &mut self.volatile,
+                &mut self.entity_storage,
+                [cause.clone()],
+                &mut result,
+            ) {
+                trace!("skip disabled state handler {}", handler.name);
+                continue;
+            }
+            trace!("invoke state handler {} for {}", handler.name, entity);
+            result += invoke_user_code(
+                &mut self.volatile,
+                &self.stable,
+                &mut self.entity_storage,
+                handler.name,
+                handler.module,
+                [cause.clone()],
+                [UserCode::new(|ctx| {
+                    (handler.callback)(ctx, entity.export())
+                })],
+                |_| {},
+                &(),
+            );
+        }
+        result
+    }
+}
+
+// transitions of a state queued in a single step are collapsed to the last one, so the committed
+// state is exited once and the final one is entered once
+fn last_transitions(transitions: Vec<StateTransition>) -> Vec<StateTransition> {
+    let mut seen = HashSet::new();
+    let mut transitions = transitions
+        .into_iter()
+        .rev()
+        .filter(|it| seen.insert(it.component_key))
+        .to_vec();
+    transitions.reverse();
+    transitions
+}
+
+impl VolatileWorld {
+    pub(crate) fn transition_state_internal(
+        &mut self,
+        component_key: ComponentKey,
+        assign: ComponentModification,
+    ) {
+        self.state_transitions.push(StateTransition {
+            component_key,
+            assign,
+            cause: self.current_cause.clone(),
+        });
+    }
+}
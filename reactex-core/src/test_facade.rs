@@ -1,9 +1,15 @@
+use crate::aggregate::EcsAggregate;
+use crate::bundle::EcsBundle;
 use crate::component::EcsComponent;
+use crate::derived::DeriveFn;
 use crate::entity_key::EntityKey;
 use crate::filter::FilterDesc;
+use crate::index::EcsIndexed;
 use crate::internal::world_configure::ConfigurableWorld;
 use crate::internal::world_core::World;
 use crate::internal::world_pipeline::execute_all_internal;
+use crate::state::EcsState;
+use crate::world_result::QueryError;
 use crate::world_result::WorldResult;
 use crate::Ctx;
 use std::panic::RefUnwindSafe;
@@ -40,7 +46,8 @@ impl ConfigurableWorld {
         filter_key: FilterDesc,
         callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
     ) {
-        self.fetus.add_disappear_handler(name, filter_key, callback)
+        self.fetus
+            .add_disappear_handler(name, filter_key, true, callback)
     }
 
     pub fn add_appear_handler(
@@ -49,7 +56,81 @@ impl ConfigurableWorld {
         filter_key: FilterDesc,
         callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
     ) {
-        self.fetus.add_appear_handler(name, filter_key, callback)
+        self.fetus
+            .add_appear_handler(name, filter_key, true, callback)
+    }
+
+    pub fn add_state_enter_handler<S: EcsState>(
+        &mut self,
+        name: &'static str,
+        filter_key: FilterDesc,
+        state: fn(&S) -> bool,
+        callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        self.fetus
+            .add_state_enter_handler(name, filter_key, state, callback)
+    }
+
+    pub fn add_state_exit_handler<S: EcsState>(
+        &mut self,
+        name: &'static str,
+        filter_key: FilterDesc,
+        state: fn(&S) -> bool,
+        callback: impl Fn(Ctx, EntityKey) + RefUnwindSafe + 'static,
+    ) {
+        self.fetus
+            .add_state_exit_handler(name, filter_key, state, callback)
+    }
+
+    pub fn register_index<C: EcsIndexed>(
+        &mut self,
+        extract: impl Fn(&C) -> C::IndexKey + RefUnwindSafe + 'static,
+    ) {
+        self.fetus.register_index(extract)
+    }
+
+    pub fn register_unique_index<C: EcsIndexed>(
+        &mut self,
+        extract: impl Fn(&C) -> C::IndexKey + RefUnwindSafe + 'static,
+    ) {
+        self.fetus.register_unique_index(extract)
+    }
+
+    #[cfg(feature = "spatial")]
+    pub fn register_spatial_index<C: EcsComponent, const N: usize>(
+        &mut self,
+        cell_size: f32,
+        position: impl Fn(&C) -> [f32; N] + RefUnwindSafe + 'static,
+    ) {
+        self.fetus.register_spatial_index(cell_size, position)
+    }
+
+    /// `Timer` and `TimeToLive` are advanced on every signal `T` (e.g. a tick one)
+    /// instead of every transaction.
+    pub fn advance_timers_on<T: RefUnwindSafe + 'static>(&mut self) {
+        self.fetus.advance_timers_on::<T>()
+    }
+
+    /// Maintains `A` over entities matched by the filter, folding items extracted from
+    /// their component `C`. The filter must include `C`.
+    pub fn add_aggregate<A: EcsAggregate, C: EcsComponent>(
+        &mut self,
+        filter: FilterDesc,
+        extract: impl Fn(&C) -> A::Item + RefUnwindSafe + 'static,
+    ) {
+        self.fetus.add_aggregate::<A, C>(filter, extract)
+    }
+
+    /// Keeps `D` computed from the sources of `derive` (e.g. `fn(&A, &B) -> D`):
+    /// it's added when an entity gets all of them, recomputed when any of them is modified
+    /// and removed when any of them is removed. Switches and run conditions of the module
+    /// don't apply to it, so `D` stays consistent while the module is disabled.
+    pub fn add_derived_component<S, D: EcsComponent>(
+        &mut self,
+        name: &'static str,
+        derive: impl DeriveFn<S, D>,
+    ) {
+        self.fetus.add_derived_component(name, derive)
     }
 }
 
@@ -86,6 +167,26 @@ impl World {
     pub fn query(&mut self, filter: FilterDesc) -> impl Iterator<Item = EntityKey> + '_ {
         self.stable.query(filter)
     }
+
+    pub fn query_count(&self, filter: FilterDesc) -> usize {
+        self.stable.query_count(filter)
+    }
+
+    pub fn query_is_empty(&self, filter: FilterDesc) -> bool {
+        self.stable.query_is_empty(filter)
+    }
+
+    pub fn query_first(&self, filter: FilterDesc) -> Option<EntityKey> {
+        self.stable.query_first(filter)
+    }
+
+    pub fn query_single(&self, filter: FilterDesc) -> Result<EntityKey, QueryError> {
+        self.stable.query_single(filter)
+    }
+
+    pub fn query_contains(&self, filter: FilterDesc, entity: EntityKey) -> bool {
+        self.stable.query_contains(filter, entity)
+    }
 }
 
 // work with components
@@ -107,6 +208,11 @@ impl World {
             .modify_component(entity, change, entity_storage)
     }
 
+    pub fn set_component<T: EcsComponent>(&mut self, entity: EntityKey, value: T) -> WorldResult {
+        let entity_storage = &self.entity_storage;
+        self.volatile.set_component(entity, value, entity_storage)
+    }
+
     pub fn add_component<T: EcsComponent>(
         &mut self,
         entity: EntityKey,
@@ -122,4 +228,15 @@ impl World {
         self.volatile
             .remove_component::<T>(entity, entity_storage, &self.stable.component_mappings)
     }
+
+    pub fn add_bundle<T: EcsBundle>(&mut self, entity: EntityKey, bundle: T) -> WorldResult {
+        let entity_storage = &self.entity_storage;
+        self.volatile.add_bundle(entity, bundle, entity_storage)
+    }
+
+    pub fn remove_bundle<T: EcsBundle>(&mut self, entity: EntityKey) -> WorldResult {
+        let entity_storage = &self.entity_storage;
+        self.volatile
+            .remove_bundle::<T>(entity, entity_storage, &self.stable.component_mappings)
+    }
 }
@@ -0,0 +1,201 @@
+use crate::internal::execution::ExecutionResult;
+use crate::EcsContainer;
+use log::trace;
+use std::cell::Cell;
+use std::mem;
+use std::panic::UnwindSafe;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Source of monotonic time for `TickRunner`, measured from an arbitrary origin.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Clock advanced by hand, clones share the same time.
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Signal of a fixed timestep update, `tick` is its number starting from 1.
+#[derive(Copy, Clone, Debug)]
+pub struct FixedUpdate {
+    pub dt: Duration,
+    pub tick: u64,
+}
+
+/// Signal sent once per frame after fixed updates. `alpha` is the fraction of the fixed step
+/// accumulated but not simulated yet, used to interpolate between the last two updates.
+#[derive(Copy, Clone, Debug)]
+pub struct Render {
+    pub dt: Duration,
+    pub alpha: f32,
+}
+
+/// Drives a container with `FixedUpdate` signals at a fixed rate and a `Render` signal per frame.
+/// Every signal is sent in a separate transaction, signals without handlers aren't sent.
+pub struct TickRunner<C: Clock = SystemClock> {
+    clock: C,
+    step: Duration,
+    max_catch_up: u32,
+    accumulator: Duration,
+    last_frame: Option<Duration>,
+    paused: bool,
+    requested_steps: u32,
+    tick: u64,
+}
+
+impl TickRunner<SystemClock> {
+    pub fn new(step: Duration) -> TickRunner<SystemClock> {
+        TickRunner::with_clock(SystemClock::new(), step)
+    }
+}
+
+impl<C: Clock> TickRunner<C> {
+    pub fn with_clock(clock: C, step: Duration) -> TickRunner<C> {
+        assert!(!step.is_zero(), "fixed step should be positive");
+        TickRunner {
+            clock,
+            step,
+            max_catch_up: 5,
+            accumulator: Duration::ZERO,
+            last_frame: None,
+            paused: false,
+            requested_steps: 0,
+            tick: 0,
+        }
+    }
+
+    /// Limits fixed updates per frame (5 by default). Time of the steps above the limit is
+    /// dropped, so a long frame (e.g. a debugger pause) doesn't make the simulation spiral.
+    pub fn max_catch_up(mut self, steps: u32) -> TickRunner<C> {
+        assert!(steps > 0, "at least one step per frame should be allowed");
+        self.max_catch_up = steps;
+        self
+    }
+
+    /// While paused the time doesn't accumulate and only requested steps are updated.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Requests fixed updates on the next frame in addition to the due ones, even if paused.
+    pub fn step(&mut self, steps: u32) {
+        self.requested_steps += steps;
+    }
+
+    /// Number of fixed updates so far.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn frame(&mut self, ecs: &mut EcsContainer) -> ExecutionResult {
+        let now = self.clock.now();
+        let dt = match self.last_frame {
+            None => Duration::ZERO,
+            Some(last_frame) => now.saturating_sub(last_frame),
+        };
+        self.last_frame = Some(now);
+
+        let mut steps = mem::take(&mut self.requested_steps);
+        if !self.paused {
+            self.accumulator += dt;
+            let due = self.accumulator.as_nanos() / self.step.as_nanos();
+            let run = due.min(self.max_catch_up as u128) as u32;
+            self.accumulator -= self.step * run;
+            if due > run as u128 {
+                trace!("dropping {} fixed steps", due - run as u128);
+                self.accumulator = Duration::from_nanos(
+                    (self.accumulator.as_nanos() % self.step.as_nanos()) as u64,
+                );
+            }
+            steps += run;
+        }
+
+        let mut result = ExecutionResult::new();
+        for _ in 0..steps {
+            self.tick += 1;
+            let update = FixedUpdate {
+                dt: self.step,
+                tick: self.tick,
+            };
+            result += send(ecs, "fixed_update", update);
+        }
+        let alpha = self.accumulator.as_secs_f32() / self.step.as_secs_f32();
+        result += send(ecs, "render", Render { dt, alpha });
+        result
+    }
+}
+
+fn send<T: UnwindSafe + 'static>(
+    ecs: &mut EcsContainer,
+    name: &'static str,
+    signal: T,
+) -> ExecutionResult {
+    if !ecs.has_signal_handlers::<T>() {
+        return ExecutionResult::new();
+    }
+    let (_, result) = ecs.execute_once(name, move |ctx| ctx.send_signal(signal));
+    result
+}
+
+impl EcsContainer {
+    /// Runs frames back to back until `after_frame` returns `false`. Pacing (vsync, sleeping)
+    /// and pausing are up to `after_frame`, it gets the result of the frame.
+    pub fn run_loop<C: Clock>(
+        &mut self,
+        runner: &mut TickRunner<C>,
+        mut after_frame: impl FnMut(&mut EcsContainer, &mut TickRunner<C>, ExecutionResult) -> bool,
+    ) {
+        loop {
+            let result = runner.frame(self);
+            if !after_frame(self, runner, result) {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::component::EcsComponent;
+use crate::internal::cause::Cause;
+use crate::internal::component_key::ComponentKey;
+use crate::internal::execution::ExecutionError;
+use crate::internal::world_extras::InternalEntityKey;
+use crate::ExecutionResult;
+use crate::VolatileWorld;
+use crate::World;
+use std::any::type_name;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::mem;
+use std::panic::RefUnwindSafe;
+use to_vec::ToVec;
+
+/// Sends the signal to its entity after the given number of ticks, then removes itself.
+/// The signal is received by entity signal handlers of the entity only.
+pub struct Timer {
+    pub ticks: u32,
+    signal: Box<dyn TimerSignal>,
+}
+
+impl Timer {
+    pub fn new<T: Clone + RefUnwindSafe + 'static>(ticks: u32, signal: T) -> Timer {
+        Timer {
+            ticks,
+            signal: Box::new(signal),
+        }
+    }
+}
+
+impl Debug for Timer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Timer")
+            .field("ticks", &self.ticks)
+            .field("signal", &self.signal.signal_type_name())
+            .finish()
+    }
+}
+
+/// Destroys its entity after the given number of ticks.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TimeToLive(pub u32);
+
+// built-in components, so their indexes are reserved instead of being generated by derive
+impl EcsComponent for Timer {
+    const INDEX: u16 = u16::MAX - 1;
+    const NAME: &'static str = concat!(module_path!(), "::Timer");
+}
+
+impl EcsComponent for TimeToLive {
+    const INDEX: u16 = u16::MAX - 2;
+    const NAME: &'static str = concat!(module_path!(), "::TimeToLive");
+}
+
+#[ctor::ctor]
+fn register_type_callback_timers() {
+    World::register_type(|world| {
+        world.register_component::<Timer>();
+        world.register_component::<TimeToLive>();
+    });
+}
+
+trait TimerSignal: RefUnwindSafe {
+    fn signal_type_id(&self) -> TypeId;
+
+    fn signal_type_name(&self) -> &'static str;
+
+    fn send(&self, volatile: &mut VolatileWorld, target: InternalEntityKey);
+}
+
+impl<T: Clone + RefUnwindSafe + 'static> TimerSignal for T {
+    fn signal_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn signal_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn send(&self, volatile: &mut VolatileWorld, target: InternalEntityKey) {
+        volatile.signal_to(target, self.clone());
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TimerStorage {
+    // cause of setting every running timer, expiry is its consequence
+    causes: HashMap<ComponentKey, Cause>,
+    // timers are advanced on this signal instead of every transaction
+    pub(crate) tick_signal: Option<TypeId>,
+}
+
+impl TimerStorage {
+    pub(crate) fn on_added(
+        &mut self,
+        component_key: ComponentKey,
+        causes: impl Iterator<Item = Cause>,
+    ) {
+        if is_timer(component_key) {
+            self.causes
+                .insert(component_key, Cause::consequence("set_timer", causes));
+        }
+    }
+
+    pub(crate) fn on_removed(&mut self, component_key: ComponentKey) {
+        if is_timer(component_key) {
+            self.causes.remove(&component_key);
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.causes.shrink_to_fit();
+    }
+}
+
+fn is_timer(component_key: ComponentKey) -> bool {
+    component_key.component_type == Timer::get_component_type()
+        || component_key.component_type == TimeToLive::get_component_type()
+}
+
+impl World {
+    pub(crate) fn advance_timers_on<T: RefUnwindSafe + 'static>(&mut self) {
+        self.add_global_signal_handler::<T>("advance_timers", |_| {});
+        self.stable.timers.tick_signal = Some(TypeId::of::<T>());
+    }
+
+    pub(crate) fn advance_timers_every_transaction(&mut self, result: &mut ExecutionResult) {
+        if self.stable.timers.tick_signal.is_none() {
+            *result += self.advance_timers();
+        }
+    }
+
+    pub(crate) fn advance_timers(&mut self) -> ExecutionResult {
+        let mut result = ExecutionResult::new();
+        let mut timers = self
+            .stable
+            .timers
+            .causes
+            .iter()
+            .map(|(key, cause)| (*key, cause.clone()))
+            .to_vec();
+        timers.sort_by_key(|(it, _)| it.entity);
+        let prev_cause = mem::replace(&mut self.volatile.current_cause, Cause::initial());
+        for (component_key, cause) in timers {
+            let entity = component_key.entity;
+            if component_key.component_type == TimeToLive::get_component_type() {
+                if self.count_down::<TimeToLive>(component_key) {
+                    self.volatile.current_cause = Cause::consequence("time_to_live", [cause]);
+                    self.volatile
+                        .destroy_entity_internal(entity, &mut self.entity_storage);
+                }
+                continue;
+            }
+            if !self.count_down::<Timer>(component_key) {
+                continue;
+            }
+            self.volatile.current_cause = Cause::consequence("timer_expired", [cause]);
+            let _ = self
+                .volatile
+                .remove_component_internal(component_key, &self.stable.component_mappings);
+            let signal: &dyn TimerSignal = &*self
+                .stable
+                .get_component_no_validation::<Timer>(entity.index)
+                .unwrap()
+                .signal;
+            if self
+                .volatile
+                .signal_storage
+                .payloads
+                .contains_key(&signal.signal_type_id())
+            {
+                signal.send(&mut self.volatile, entity);
+            } else {
+                let message = format!(
+                    "timer of entity {} expired, but there are no handlers for signal {}",
+                    entity,
+                    signal.signal_type_name()
+                );
+                result.errors.push(ExecutionError::violation(
+                    message,
+                    self.volatile.current_cause.clone(),
+                ));
+            }
+        }
+        self.volatile.current_cause = prev_cause;
+        result
+    }
+
+    // returns `true` if the committed value is expired, otherwise schedules decrement of it
+    fn count_down<T: EcsComponent + Ticks>(&mut self, component_key: ComponentKey) -> bool {
+        let ticks = self
+            .stable
+            .get_component_no_validation::<T>(component_key.entity.index)
+            .map(|it| it.ticks());
+        match ticks {
+            None => false,
+            Some(0 | 1) => true,
+            Some(_) => {
+                self.volatile.modify_component_internal(
+                    component_key,
+                    Box::new(|value| *value.downcast_mut::<T>().unwrap().ticks_mut() -= 1),
+                );
+                false
+            }
+        }
+    }
+}
+
+trait Ticks {
+    fn ticks(&self) -> u32;
+
+    fn ticks_mut(&mut self) -> &mut u32;
+}
+
+impl Ticks for Timer {
+    fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    fn ticks_mut(&mut self) -> &mut u32 {
+        &mut self.ticks
+    }
+}
+
+impl Ticks for TimeToLive {
+    fn ticks(&self) -> u32 {
+        self.0
+    }
+
+    fn ticks_mut(&mut self) -> &mut u32 {
+        &mut self.0
+    }
+}
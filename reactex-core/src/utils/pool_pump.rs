@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 use std::panic::RefUnwindSafe;
 
+use crate::utils::pools::is_tag;
 use crate::utils::pools::AbstractPool;
 use crate::utils::pools::PoolKey;
 
@@ -41,8 +42,12 @@ impl<
             .specializable_mut()
             .try_specialize::<TValue>()
             .unwrap()
-            .del_and_get(key)
-            .unwrap();
+            .del_and_get(key);
+        let Some(value) = value else {
+            assert!(is_tag::<TKeySrc, TValue>(), "framework BUG: moved value not found");
+            // the destination got the shared instance of the tag already
+            return TKeyDst::from_usize(0);
+        };
         dst.specializable_mut()
             .try_specialize::<TValue>()
             .unwrap()
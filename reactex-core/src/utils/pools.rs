@@ -1,12 +1,21 @@
 use std::any::Any;
 use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::mem;
 use std::panic::RefUnwindSafe;
 
 pub struct SpecificPool<K, V> {
     pd: PhantomData<K>,
     buffer: Vec<Option<V>>,
     holes: VecDeque<usize>,
+    cloner: Option<fn(&V) -> V>,
+}
+
+// values of zero-sized types (tags) are interchangeable, so component pools store a single shared
+// instance of a tag at index 0 and its keys mean membership only. Other pools, e.g. of signal
+// payloads, take the value back on each delete, so they store tags as any other value
+pub(crate) const fn is_tag<K: PoolKey, V>() -> bool {
+    K::SHARES_TAGS && mem::size_of::<V>() == 0
 }
 
 pub trait AbstractPool<K>: RefUnwindSafe {
@@ -14,12 +23,17 @@ pub trait AbstractPool<K>: RefUnwindSafe {
     fn add(&mut self, value: Box<dyn Any>) -> K;
     fn clear(&mut self);
     fn get_any_mut(&mut self, key: &K) -> Option<&mut dyn Any>;
+    fn clone_any(&self, key: &K) -> Option<Box<dyn Any>>;
+    fn shrink_to_fit(&mut self);
+    fn reserve(&mut self, additional: usize);
 
     fn specializable_mut(&mut self) -> SpecializablePoolMut<K>;
     fn specializable(&self) -> SpecializablePool<K>;
 }
 
 pub trait PoolKey: 'static {
+    const SHARES_TAGS: bool = false;
+
     fn as_usize(&self) -> usize;
     fn from_usize(value: usize) -> Self;
 }
@@ -52,10 +66,26 @@ impl<K: PoolKey, V> SpecificPool<K, V> {
             pd: Default::default(),
             buffer: vec![],
             holes: Default::default(),
+            cloner: None,
         }
     }
 
+    pub fn enable_clone(&mut self)
+    where
+        V: Clone,
+    {
+        self.cloner = Some(V::clone);
+    }
+
     pub fn add(&mut self, value: V) -> K {
+        if is_tag::<K, V>() {
+            match self.buffer.first_mut() {
+                None => self.buffer.push(Some(value)),
+                Some(instance @ None) => *instance = Some(value),
+                Some(Some(_)) => {}
+            }
+            return K::from_usize(0);
+        }
         K::from_usize(match self.holes.pop_front() {
             None => {
                 self.buffer.push(Some(value));
@@ -82,6 +112,10 @@ impl<K: PoolKey, V> SpecificPool<K, V> {
     }
 
     fn del_internal(&mut self, key: &K) -> Option<V> {
+        if is_tag::<K, V>() {
+            // the shared instance is kept for other keys
+            return None;
+        }
         let index = key.as_usize();
         if index < self.buffer.len() {
             if index == self.buffer.len() - 1 {
@@ -100,9 +134,31 @@ impl<K: PoolKey, V> SpecificPool<K, V> {
     }
 
     pub(crate) fn del_and_get(&mut self, key: &K) -> Option<V> {
+        if is_tag::<K, V>() {
+            return self.buffer.first_mut().and_then(Option::take);
+        }
         self.del_internal(key)
     }
 
+    // values are never moved, so only the free entries after the last value are released
+    fn shrink_to_fit_internal(&mut self) {
+        while let Some(None) = self.buffer.last() {
+            self.buffer.pop();
+        }
+        let len = self.buffer.len();
+        self.holes.retain(|it| *it < len);
+        self.buffer.shrink_to_fit();
+        self.holes.shrink_to_fit();
+    }
+
+    // holes are filled first, so only the rest needs room at the end
+    fn reserve_internal(&mut self, additional: usize) {
+        if !is_tag::<K, V>() {
+            self.buffer
+                .reserve(additional.saturating_sub(self.holes.len()));
+        }
+    }
+
     fn clear_internal(&mut self) {
         self.holes.clear();
         self.buffer.clear();
@@ -129,6 +185,19 @@ impl<K: PoolKey + RefUnwindSafe + 'static, V: RefUnwindSafe + 'static> AbstractP
         self.get_mut(key).map(|it| it as &mut dyn Any)
     }
 
+    fn clone_any(&self, key: &K) -> Option<Box<dyn Any>> {
+        let cloner = self.cloner?;
+        self.get(key).map(|it| Box::new(cloner(it)) as Box<dyn Any>)
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit_internal();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.reserve_internal(additional);
+    }
+
     fn specializable_mut(&mut self) -> SpecializablePoolMut<K> {
         SpecializablePoolMut {
             pd: Default::default(),
@@ -157,6 +226,7 @@ impl PoolKey for usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::internal::component_pool_manager::ComponentDataKey;
 
     #[test]
     fn downcast_works() {
@@ -165,4 +235,56 @@ mod tests {
         assert!(ints.specializable_mut().try_specialize::<i32>().is_some());
         assert!(bools.specializable_mut().try_specialize::<i32>().is_none())
     }
+
+    #[test]
+    fn trailing_entries_released_on_shrink() {
+        let mut ints = SpecificPool::<usize, i32>::new();
+        let keys = (0..10).map(|it| ints.add(it)).collect::<Vec<_>>();
+        for key in &keys[1..9] {
+            AbstractPool::del(&mut ints, key);
+        }
+        AbstractPool::del(&mut ints, &keys[9]);
+
+        AbstractPool::shrink_to_fit(&mut ints);
+
+        assert_eq!(ints.buffer.len(), 1);
+        assert!(ints.holes.is_empty());
+        assert_eq!(ints.get(&keys[0]), Some(&0));
+        assert_eq!(ints.add(42), 1);
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Tag;
+
+    #[test]
+    fn tag_instance_shared() {
+        let mut tags = SpecificPool::<ComponentDataKey, Tag>::new();
+        let first = tags.add(Tag);
+        let second = tags.add(Tag);
+        AbstractPool::del(&mut tags, &first);
+
+        assert_eq!(first, second);
+        assert_eq!(tags.buffer.len(), 1);
+        assert_eq!(tags.get(&second), Some(&Tag));
+    }
+
+    #[test]
+    fn tag_instance_not_shared_by_other_pools() {
+        let mut tags = SpecificPool::<usize, Tag>::new();
+        let first = tags.add(Tag);
+        let second = tags.add(Tag);
+
+        assert_eq!(tags.del_and_get(&first), Some(Tag));
+        assert_eq!(tags.del_and_get(&second), Some(Tag));
+    }
+
+    #[test]
+    fn clone_works_only_if_enabled() {
+        let mut ints = SpecificPool::<usize, i32>::new();
+        let key = ints.add(42);
+        assert!(AbstractPool::clone_any(&ints, &key).is_none());
+        ints.enable_clone();
+        let cloned = AbstractPool::clone_any(&ints, &key).unwrap();
+        assert_eq!(cloned.downcast_ref::<i32>(), Some(&42));
+    }
 }
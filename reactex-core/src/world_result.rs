@@ -1,3 +1,4 @@
+use crate::component::ComponentType;
 use justerror::Error;
 
 pub type WorldResult<T = ()> = Result<T, WorldError>;
@@ -7,12 +8,16 @@ pub type WorldResult<T = ()> = Result<T, WorldError>;
 pub enum WorldError {
     Entity(#[from] EntityError),
     Component(#[from] ComponentError),
+    Prefab(#[from] PrefabError),
+    /// The transaction panicked, its errors are reported in the `ExecutionResult`.
+    Aborted,
 }
 
 #[Error]
 #[derive(Eq, PartialEq)]
 pub enum ComponentError {
     NotFound,
+    NotClonable(ComponentType),
 }
 
 #[Error]
@@ -22,3 +27,23 @@ pub enum EntityError {
     NotCommitted,
     IsStale,
 }
+
+#[Error]
+#[derive(Eq, PartialEq)]
+pub enum QueryError {
+    NotFound,
+    NotUnique,
+}
+
+#[Error]
+#[derive(Eq, PartialEq)]
+pub enum PrefabError {
+    NotFound(String),
+    Parse(String),
+    UnknownComponent(String),
+    AmbiguousComponent(String),
+    InvalidComponent(String, String),
+    MissingComponent(String),
+    OverrideNotSupported(String),
+    InvalidOverride(String, String),
+}
@@ -0,0 +1,158 @@
+use reactex_core::ecs_filter;
+use reactex_core::EcsAggregate;
+use reactex_core::EcsContainer;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug)]
+struct Enemy {}
+
+#[derive(EcsComponent, Debug)]
+struct Gold {
+    amount: i64,
+}
+
+#[derive(Default, Debug)]
+struct EnemyCount(usize);
+
+impl EcsAggregate for EnemyCount {
+    type Item = ();
+
+    fn add(&mut self, _: &()) {
+        self.0 += 1;
+    }
+
+    fn remove(&mut self, _: &()) -> bool {
+        self.0 -= 1;
+        true
+    }
+}
+
+#[derive(Default, Debug)]
+struct TotalGold(i64);
+
+impl EcsAggregate for TotalGold {
+    type Item = i64;
+
+    fn add(&mut self, item: &i64) {
+        self.0 += item;
+    }
+
+    fn remove(&mut self, item: &i64) -> bool {
+        self.0 -= item;
+        true
+    }
+}
+
+#[derive(Default, Debug)]
+struct RichestEnemy(Option<i64>);
+
+impl EcsAggregate for RichestEnemy {
+    type Item = i64;
+
+    fn add(&mut self, item: &i64) {
+        self.0 = self.0.max(Some(*item));
+    }
+
+    fn remove(&mut self, _: &i64) -> bool {
+        false
+    }
+}
+
+fn container() -> EcsContainer {
+    EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_aggregate::<EnemyCount, Enemy>(ecs_filter!(Enemy), |_| ());
+            world.add_aggregate::<TotalGold, Gold>(ecs_filter!(Gold), |it| it.amount);
+            world.add_aggregate::<RichestEnemy, Gold>(ecs_filter!(Enemy, Gold), |it| it.amount);
+        })
+        .seal()
+}
+
+fn read(ecs: &mut EcsContainer) -> (usize, i64, Option<i64>) {
+    let (result, _) = ecs.execute_once("read", |ctx| {
+        (
+            ctx.aggregate::<EnemyCount>().0,
+            ctx.aggregate::<TotalGold>().0,
+            ctx.aggregate::<RichestEnemy>().0,
+        )
+    });
+    result.unwrap()
+}
+
+#[test]
+fn aggregates_updated_on_appear() {
+    let mut ecs = container();
+    ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Enemy {}).add(Gold { amount: 10 });
+        ctx.create_entity().add(Enemy {}).add(Gold { amount: 30 });
+        ctx.create_entity().add(Gold { amount: 100 });
+    });
+
+    assert_eq!(read(&mut ecs), (2, 140, Some(30)));
+}
+
+#[test]
+fn aggregates_updated_on_disappear() {
+    let mut ecs = container();
+    let (entities, _) = ecs.execute_once("test", |ctx| {
+        [
+            ctx.create_entity().add(Enemy {}).add(Gold { amount: 10 }).key(),
+            ctx.create_entity().add(Enemy {}).add(Gold { amount: 30 }).key(),
+        ]
+    });
+    let [first, second] = entities.unwrap();
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(first).unwrap().remove::<Gold>();
+        ctx.get_entity(second).unwrap().destroy();
+    });
+
+    assert_eq!(read(&mut ecs), (1, 0, None));
+}
+
+#[test]
+fn aggregates_updated_on_modification() {
+    let mut ecs = container();
+    let (entities, _) = ecs.execute_once("test", |ctx| {
+        [
+            ctx.create_entity().add(Enemy {}).add(Gold { amount: 10 }).key(),
+            ctx.create_entity().add(Enemy {}).add(Gold { amount: 30 }).key(),
+        ]
+    });
+    let [first, second] = entities.unwrap();
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(first).unwrap().modify::<Gold>(|it| it.amount = 5);
+        ctx.get_entity(second).unwrap().set(Gold { amount: 20 });
+    });
+
+    assert_eq!(read(&mut ecs), (2, 25, Some(20)));
+}
+
+#[test]
+fn handlers_see_updated_aggregates() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_aggregate::<EnemyCount, Enemy>(ecs_filter!(Enemy), |_| ());
+            world.add_appear_handler("check", ecs_filter!(Enemy), |ctx, _| {
+                assert_eq!(ctx.aggregate::<EnemyCount>().0, 1);
+            });
+        })
+        .seal();
+
+    let (_, result) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Enemy {});
+    });
+
+    assert!(result.errors.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "aggregate is not registered")]
+fn unregistered_aggregate_panics() {
+    let mut ecs = EcsContainer::create().seal();
+    let (_, result) = ecs.execute_once("test", |ctx| ctx.aggregate::<EnemyCount>().0);
+    if let Some(error) = result.errors.into_iter().next() {
+        panic!("{}", error.details.message);
+    }
+}
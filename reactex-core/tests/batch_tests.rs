@@ -0,0 +1,220 @@
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_core::World;
+use reactex_macro::EcsBundle;
+use reactex_macro::EcsComponent;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct B {
+    value: i32,
+}
+
+#[derive(EcsBundle)]
+struct AB {
+    a: A,
+    b: B,
+}
+
+fn spawn(ecs: &mut EcsContainer, count: i32) -> Vec<EntityKey> {
+    let (keys, _) = ecs.execute_once("spawn", move |ctx| {
+        ctx.spawn_batch((0..count).map(|i| AB {
+            a: A { value: i },
+            b: B { value: -i },
+        }))
+    });
+    keys.unwrap()
+}
+
+#[test]
+fn batch_spawned_with_components() {
+    let mut ecs = EcsContainer::create().seal();
+
+    let keys = spawn(&mut ecs, 1000);
+
+    let (result, _) = ecs.execute_once("read", move |ctx| {
+        keys.iter()
+            .map(|key| {
+                let entity = ctx.get_entity(*key).unwrap();
+                (
+                    entity.get::<A>().unwrap().value,
+                    entity.get::<B>().unwrap().value,
+                )
+            })
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        result.unwrap(),
+        (0..1000).map(|i| (i, -i)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn batch_spawned_entities_not_visible_before_commit() {
+    let mut ecs = EcsContainer::create().seal();
+
+    let (result, _) = ecs.execute_once("spawn", |ctx| {
+        let keys = ctx.spawn_batch((0..3).map(|i| AB {
+            a: A { value: i },
+            b: B { value: i },
+        }));
+        ctx.get_entity(keys[0]).is_none()
+    });
+
+    assert_eq!(result, Some(true));
+}
+
+#[test]
+fn appear_event_delivered_for_each_spawned_entity() {
+    let matched = Rc::new(Mutex::new(Vec::new()));
+    let mut ecs = {
+        let matched = matched.clone();
+        EcsContainer::create()
+            .configure_in_test(|world| {
+                world.add_appear_handler("test", ecs_filter!(A, B), move |_, entity| {
+                    matched.lock().unwrap().push(entity)
+                })
+            })
+            .seal()
+    };
+
+    let mut keys = spawn(&mut ecs, 100);
+
+    let mut matched = matched.lock().unwrap().clone();
+    matched.sort();
+    keys.sort();
+    assert_eq!(matched, keys);
+}
+
+#[test]
+fn matched_entities_destroyed() {
+    World::register_query(ecs_filter!(A));
+    let mut ecs = EcsContainer::create().seal();
+    let keys = spawn(&mut ecs, 10);
+    let (other, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(B { value: 0 }).key());
+
+    let (count, _) = ecs.execute_once("test", |ctx| ctx.destroy_all(ecs_filter!(A)));
+
+    assert_eq!(count, Some(10));
+    let (result, _) = ecs.execute_once("read", move |ctx| {
+        (
+            keys.iter().all(|key| ctx.get_entity(*key).is_none()),
+            ctx.get_entity(other.unwrap()).is_some(),
+        )
+    });
+    assert_eq!(result, Some((true, true)));
+}
+
+#[test]
+fn disappear_event_delivered_for_each_destroyed_entity() {
+    World::register_query(ecs_filter!(A));
+    let matched = Rc::new(Mutex::new(Vec::new()));
+    let mut ecs = {
+        let matched = matched.clone();
+        EcsContainer::create()
+            .configure_in_test(|world| {
+                world.add_disappear_handler("test", ecs_filter!(A), move |_, entity| {
+                    matched.lock().unwrap().push(entity)
+                })
+            })
+            .seal()
+    };
+    let mut keys = spawn(&mut ecs, 5);
+
+    ecs.execute_once("test", |ctx| ctx.destroy_all(ecs_filter!(A)));
+
+    let mut matched = matched.lock().unwrap().deref().clone();
+    matched.sort();
+    keys.sort();
+    assert_eq!(matched, keys);
+}
+
+#[derive(EcsComponent, Debug, Default, Clone, Eq, PartialEq)]
+struct Marker {}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+#[ecs(requires(Marker))]
+struct Cell {
+    #[ecs(index)]
+    value: i32,
+}
+
+#[derive(EcsBundle)]
+struct ACell {
+    a: A,
+    cell: Cell,
+}
+
+#[test]
+fn batch_spawned_with_required_and_indexed_components() {
+    World::register_query(ecs_filter!(A, Marker));
+    let mut ecs = EcsContainer::create().seal();
+
+    let (keys, _) = ecs.execute_once("spawn", |ctx| {
+        ctx.spawn_batch((0..10).map(|i| ACell {
+            a: A { value: i },
+            cell: Cell { value: i % 2 },
+        }))
+    });
+    let keys = keys.unwrap();
+
+    let (result, _) = ecs.execute_once("read", |ctx| {
+        let mut odd = ctx.lookup::<Cell>(&1).map(|it| it.key()).collect::<Vec<_>>();
+        odd.sort();
+        (odd, ctx.query(ecs_filter!(A, Marker)).count())
+    });
+    let odd = keys.iter().skip(1).step_by(2).copied().collect::<Vec<_>>();
+    assert_eq!(result, Some((odd, 10)));
+}
+
+#[test]
+fn appear_events_delivered_only_for_matched_filters() {
+    let events = Rc::new(Mutex::new(Vec::new()));
+    let mut ecs = {
+        let events = events.clone();
+        EcsContainer::create()
+            .configure_in_test(|world| {
+                for (name, filter) in [
+                    ("a", ecs_filter!(A)),
+                    ("a cell", ecs_filter!(A, Cell)),
+                    ("a cell marker", ecs_filter!(A, Cell, Marker)),
+                    ("a b", ecs_filter!(A, B)),
+                ] {
+                    let events = events.clone();
+                    world.add_appear_handler(name, filter, move |_, _| {
+                        events.lock().unwrap().push(name)
+                    });
+                }
+            })
+            .seal()
+    };
+
+    ecs.execute_once("spawn", |ctx| {
+        ctx.spawn_batch((0..2).map(|i| ACell {
+            a: A { value: i },
+            cell: Cell { value: i },
+        }))
+    });
+
+    let mut events = events.lock().unwrap().clone();
+    events.sort();
+    assert_eq!(
+        events,
+        vec![
+            "a",
+            "a",
+            "a cell",
+            "a cell",
+            "a cell marker",
+            "a cell marker"
+        ]
+    );
+}
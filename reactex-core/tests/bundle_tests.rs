@@ -0,0 +1,178 @@
+use reactex_core::ecs_filter;
+use reactex_core::ConfigurableWorld;
+use reactex_core::EcsContainer;
+use reactex_macro::EcsBundle;
+use reactex_macro::EcsComponent;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct B {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct C {}
+
+#[derive(EcsBundle)]
+struct AB {
+    a: A,
+    b: B,
+}
+
+#[derive(EcsBundle)]
+struct BC(B, C);
+
+#[derive(EcsBundle)]
+struct WithA<T> {
+    a: A,
+    other: T,
+}
+
+#[test]
+fn bundle_components_added() {
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let entity = world.create_entity();
+    world
+        .add_bundle(
+            entity,
+            AB {
+                a: A { value: 1 },
+                b: B { value: 2 },
+            },
+        )
+        .unwrap();
+    world.execute_all();
+
+    assert_eq!(world.get_component::<A>(entity).unwrap(), Some(&A { value: 1 }));
+    assert_eq!(world.get_component::<B>(entity).unwrap(), Some(&B { value: 2 }));
+}
+
+#[test]
+fn tuple_bundle_components_added() {
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let entity = world.create_entity();
+    world.add_bundle(entity, BC(B { value: 2 }, C {})).unwrap();
+    world.execute_all();
+
+    assert!(world.has_component::<B>(entity).unwrap());
+    assert!(world.has_component::<C>(entity).unwrap());
+}
+
+#[test]
+fn generic_bundle_components_added() {
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let entity = world.create_entity();
+    world
+        .add_bundle(
+            entity,
+            WithA {
+                a: A { value: 1 },
+                other: C {},
+            },
+        )
+        .unwrap();
+    world.execute_all();
+
+    assert!(world.has_component::<A>(entity).unwrap());
+    assert!(world.has_component::<C>(entity).unwrap());
+}
+
+#[test]
+fn bundle_components_removed() {
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let entity = world.create_entity();
+    world.add_bundle(entity, BC(B { value: 2 }, C {})).unwrap();
+    world.add_component(entity, A { value: 1 }).unwrap();
+    world.execute_all();
+
+    world.remove_bundle::<BC>(entity).unwrap();
+    world.execute_all();
+
+    assert!(world.has_component::<A>(entity).unwrap());
+    assert!(!world.has_component::<B>(entity).unwrap());
+    assert!(!world.has_component::<C>(entity).unwrap());
+}
+
+#[test]
+fn bundle_appears_once_with_all_causes() {
+    let matched = Rc::new(Mutex::new(Vec::new()));
+    let mut world = ConfigurableWorld::create_for_test();
+    {
+        let matched = matched.clone();
+        world.add_appear_handler("test", ecs_filter!(A, B), move |ctx, entity| {
+            let entity = ctx.get_entity(entity).unwrap();
+            matched.lock().unwrap().push((
+                entity.get::<A>().cloned(),
+                entity.get::<B>().cloned(),
+            ))
+        });
+    }
+    let mut world = world.seal();
+    let entity = world.create_entity();
+    world
+        .add_bundle(
+            entity,
+            AB {
+                a: A { value: 1 },
+                b: B { value: 2 },
+            },
+        )
+        .unwrap();
+    world.execute_all();
+
+    assert_eq!(
+        matched.lock().unwrap().deref(),
+        &vec![(Some(A { value: 1 }), Some(B { value: 2 }))]
+    );
+}
+
+#[test]
+fn bundle_added_from_handler() {
+    let mut ecs = EcsContainer::create().seal();
+    let (result, _) = ecs.execute_once("test", |ctx| {
+        let entity = ctx.create_entity().add_bundle(AB {
+            a: A { value: 1 },
+            b: B { value: 2 },
+        });
+        (entity.key(), entity.get_pending::<B>(), entity.has_pending::<C>())
+    });
+    let (entity, pending, pending_c) = result.unwrap();
+    assert_eq!((pending, pending_c), (Some(B { value: 2 }), false));
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        (entity.get::<A>().cloned(), entity.get::<B>().cloned())
+    });
+    assert_eq!(result, Some((Some(A { value: 1 }), Some(B { value: 2 }))));
+}
+
+#[test]
+fn bundle_removed_from_handler() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add_bundle(BC(B { value: 2 }, C {}))
+            .add(A { value: 1 })
+            .key()
+    });
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().remove_bundle::<BC>();
+    });
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        (
+            entity.get::<A>().is_some(),
+            entity.get::<B>().is_some(),
+            entity.get::<C>().is_some(),
+        )
+    });
+    assert_eq!(result, Some((true, false, false)));
+}
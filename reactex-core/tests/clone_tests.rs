@@ -0,0 +1,183 @@
+mod common;
+
+use common::get;
+use reactex_core::ecs_filter;
+use reactex_core::ComponentError;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_core::MapEntityKeys;
+use reactex_core::World;
+use reactex_core::WorldError;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct B {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug)]
+struct NotClonable {}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Parent {
+    key: EntityKey,
+}
+
+impl MapEntityKeys for Parent {
+    fn map_entity_keys(&mut self, map: &dyn Fn(EntityKey) -> EntityKey) {
+        self.key = map(self.key);
+    }
+}
+
+#[derive(EcsComponent, Debug, Clone)]
+struct BrokenLink {}
+
+impl MapEntityKeys for BrokenLink {
+    fn map_entity_keys(&mut self, _: &dyn Fn(EntityKey) -> EntityKey) {
+        panic!("broken mapper");
+    }
+}
+
+#[test]
+fn entity_cloned_with_all_components() {
+    let mut ecs = EcsContainer::create().seal();
+    let (source, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(A { value: 1 })
+            .add(B { value: 2 })
+            .key()
+    });
+    let source = source.unwrap();
+    let (clone, _) = ecs.execute_once("test", move |ctx| ctx.clone_entity(source).unwrap().key());
+    let clone = clone.unwrap();
+
+    assert_ne!(source, clone);
+    assert_eq!(get::<A>(&mut ecs, clone), Some(A { value: 1 }));
+    assert_eq!(get::<B>(&mut ecs, clone), Some(B { value: 2 }));
+    assert_eq!(get::<A>(&mut ecs, source), Some(A { value: 1 }));
+}
+
+#[test]
+fn entity_with_not_clonable_component_not_cloned() {
+    let mut ecs = EcsContainer::create().seal();
+    let (source, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(A { value: 1 })
+            .add(NotClonable {})
+            .key()
+    });
+    let source = source.unwrap();
+    let (result, _) = ecs.execute_once("test", move |ctx| ctx.clone_entity(source).err());
+
+    assert!(matches!(
+        result,
+        Some(Some(WorldError::Component(ComponentError::NotClonable(_))))
+    ));
+}
+
+#[test]
+fn entities_copied_between_containers() {
+    let mut editor = EcsContainer::create().seal();
+    let (source, _) = editor.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(A { value: 1 })
+            .add(B { value: 2 })
+            .key()
+    });
+    let mut game = EcsContainer::create().seal();
+
+    let (copies, result) = game.copy_entities_from(&editor, &[source.unwrap()], false);
+    let copies = copies.unwrap();
+    assert!(result.errors.is_empty());
+
+    assert_eq!(get::<A>(&mut game, copies[0]), Some(A { value: 1 }));
+    assert_eq!(get::<B>(&mut game, copies[0]), Some(B { value: 2 }));
+}
+
+#[test]
+fn entity_keys_remapped_on_copy() {
+    let mut editor = EcsContainer::create().seal();
+    // shift keys in the source container, so they differ from the keys of copies
+    editor.execute_once("test", |ctx| {
+        ctx.create_entity().add(A { value: 0 });
+    });
+    let (keys, _) = editor.execute_once("test", |ctx| {
+        let parent = ctx.create_entity().add(A { value: 1 });
+        let child = ctx.create_entity().add(Parent { key: parent.key() });
+        (parent.key(), child.key())
+    });
+    let (parent, child) = keys.unwrap();
+    let mut game = EcsContainer::create().seal();
+
+    let (copies, result) = game.copy_entities_from(&editor, &[parent, child], true);
+    let copies = copies.unwrap();
+    assert!(result.errors.is_empty());
+
+    assert_ne!(copies[0], parent);
+    assert_eq!(
+        get::<Parent>(&mut game, copies[1]),
+        Some(Parent { key: copies[0] })
+    );
+}
+
+#[test]
+fn entity_keys_not_remapped_if_not_requested() {
+    let mut editor = EcsContainer::create().seal();
+    editor.execute_once("test", |ctx| {
+        ctx.create_entity().add(A { value: 0 });
+    });
+    let (keys, _) = editor.execute_once("test", |ctx| {
+        let parent = ctx.create_entity().add(A { value: 1 });
+        let child = ctx.create_entity().add(Parent { key: parent.key() });
+        (parent.key(), child.key())
+    });
+    let (parent, child) = keys.unwrap();
+    let mut game = EcsContainer::create().seal();
+
+    let (copies, result) = game.copy_entities_from(&editor, &[parent, child], false);
+    let copies = copies.unwrap();
+    assert!(result.errors.is_empty());
+
+    assert_eq!(
+        get::<Parent>(&mut game, copies[1]),
+        Some(Parent { key: parent })
+    );
+}
+
+#[test]
+fn panicking_mapper_aborts_copy() {
+    World::register_query(ecs_filter!(BrokenLink));
+    let mut editor = EcsContainer::create().seal();
+    let (source, _) =
+        editor.execute_once("test", |ctx| ctx.create_entity().add(BrokenLink {}).key());
+    let mut game = EcsContainer::create().seal();
+
+    let (copies, result) = game.copy_entities_from(&editor, &[source.unwrap()], true);
+
+    assert_eq!(copies, Err(WorldError::Aborted));
+    assert_eq!(result.errors.len(), 1);
+    let (copied, _) = game.execute_once("test", |ctx| !ctx.query_is_empty(ecs_filter!(BrokenLink)));
+    assert_eq!(copied, Some(false));
+}
+
+#[test]
+fn errors_of_copy_transaction_returned() {
+    let mut editor = EcsContainer::create().seal();
+    let (source, _) =
+        editor.execute_once("test", |ctx| ctx.create_entity().add(A { value: 1 }).key());
+    let mut game = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_appear_handler("broken", ecs_filter!(A), |_, _| panic!("broken handler"));
+        })
+        .seal();
+
+    let (copies, result) = game.copy_entities_from(&editor, &[source.unwrap()], false);
+
+    assert_eq!(copies.unwrap().len(), 1);
+    assert_eq!(result.errors.len(), 1);
+}
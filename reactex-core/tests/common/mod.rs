@@ -0,0 +1,43 @@
+// helpers shared by integration tests, each test binary uses only some of them
+#![allow(dead_code)]
+
+use reactex_core::EcsComponent;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use std::cell::RefCell;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Tick;
+
+thread_local! {
+    static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn log(event: impl Into<String>) {
+    EVENTS.with(|it| it.borrow_mut().push(event.into()));
+}
+
+pub fn take_events() -> Vec<String> {
+    EVENTS.with(|it| it.take())
+}
+
+pub fn tick(ecs: &mut EcsContainer) {
+    let (_, result) = ecs.execute_once("tick", |ctx| ctx.send_signal(Tick));
+    assert!(result.errors.is_empty());
+}
+
+// component of the entity, `None` if either of them doesn't exist
+pub fn get<C: EcsComponent + Clone>(ecs: &mut EcsContainer, entity: EntityKey) -> Option<C> {
+    let (component, _) = ecs.execute_once("get", move |ctx| {
+        ctx.get_entity(entity).and_then(|it| it.get::<C>().cloned())
+    });
+    component.unwrap()
+}
+
+pub fn has<C: EcsComponent>(ecs: &mut EcsContainer, entity: EntityKey) -> bool {
+    let (has, _) = ecs.execute_once("has", move |ctx| {
+        ctx.get_entity(entity)
+            .is_some_and(|it| it.get::<C>().is_some())
+    });
+    has.unwrap()
+}
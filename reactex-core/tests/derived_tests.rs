@@ -0,0 +1,237 @@
+mod common;
+
+use common::get;
+use common::has;
+use reactex_core::ecs_module;
+use reactex_core::ConfigurableWorld;
+use reactex_core::EcsContainer;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct LocalPosition {
+    x: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct ParentOffset {
+    x: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct WorldPosition {
+    x: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Visible {
+    on_screen: bool,
+}
+
+ecs_module!(POSITIONS);
+
+fn positions(world: &mut ConfigurableWorld) {
+    world.add_derived_component("visible", |position: &WorldPosition| Visible {
+        on_screen: position.x < 100,
+    });
+}
+
+fn container() -> EcsContainer {
+    EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_derived_component("world_position", |local: &LocalPosition, parent: &ParentOffset| {
+                WorldPosition {
+                    x: local.x + parent.x,
+                }
+            });
+            world.add_derived_component("visible", |position: &WorldPosition| Visible {
+                on_screen: position.x < 100,
+            });
+        })
+        .seal()
+}
+
+#[test]
+fn derived_component_added_when_sources_appear() {
+    let mut ecs = container();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(LocalPosition { x: 1 })
+            .add(ParentOffset { x: 10 })
+            .key()
+    });
+
+    let entity = entity.unwrap();
+
+    assert_eq!(get(&mut ecs, entity), Some(WorldPosition { x: 11 }));
+    assert_eq!(get(&mut ecs, entity), Some(Visible { on_screen: true }));
+}
+
+#[test]
+fn derived_component_not_added_without_all_sources() {
+    let mut ecs = container();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(LocalPosition { x: 1 }).key()
+    });
+
+    let entity = entity.unwrap();
+
+    assert!(!has::<WorldPosition>(&mut ecs, entity));
+    assert!(!has::<Visible>(&mut ecs, entity));
+}
+
+#[test]
+fn derived_component_recomputed_on_modification() {
+    let mut ecs = container();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(LocalPosition { x: 1 })
+            .add(ParentOffset { x: 10 })
+            .key()
+    });
+    let entity = entity.unwrap();
+
+    let (_, result) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.modify::<LocalPosition>(|it| it.x = 50);
+        entity.set(ParentOffset { x: 60 });
+    });
+
+    assert!(result.errors.is_empty());
+    assert_eq!(get(&mut ecs, entity), Some(WorldPosition { x: 110 }));
+    assert_eq!(get(&mut ecs, entity), Some(Visible { on_screen: false }));
+}
+
+#[test]
+fn derived_component_removed_when_source_removed() {
+    let mut ecs = container();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(LocalPosition { x: 1 })
+            .add(ParentOffset { x: 10 })
+            .key()
+    });
+    let entity = entity.unwrap();
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().remove::<ParentOffset>();
+    });
+
+    assert!(!has::<WorldPosition>(&mut ecs, entity));
+    assert!(!has::<Visible>(&mut ecs, entity));
+}
+
+#[test]
+fn entity_with_derived_component_destroyed() {
+    let mut ecs = container();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(LocalPosition { x: 1 })
+            .add(ParentOffset { x: 10 })
+            .key()
+    });
+    let entity = entity.unwrap();
+
+    let (exists, result) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().destroy();
+    });
+    let (exists_after, _) = ecs.execute_once("test", move |ctx| ctx.get_entity(entity).is_some());
+
+    assert!(exists.is_some());
+    assert!(result.errors.is_empty());
+    assert_eq!(exists_after, Some(false));
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Health {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Armor {
+    value: i32,
+}
+
+#[test]
+#[should_panic(expected = "Health is derived from itself")]
+fn derivation_cycle_rejected() {
+    EcsContainer::create().configure_in_test(|world| {
+        world.add_derived_component("visible", |position: &WorldPosition| Visible {
+            on_screen: position.x < 100,
+        });
+        world.add_derived_component("armor", |health: &Health, _: &Visible| Armor {
+            value: health.value / 2,
+        });
+        world.add_derived_component("health", |armor: &Armor| Health {
+            value: armor.value * 2,
+        });
+    });
+}
+
+#[test]
+#[should_panic(expected = "Health is derived from itself")]
+fn component_derived_from_itself_rejected() {
+    EcsContainer::create().configure_in_test(|world| {
+        world.add_derived_component("health", |health: &Health| Health {
+            value: health.value,
+        });
+    });
+}
+
+#[test]
+fn derived_component_maintained_while_module_disabled() {
+    POSITIONS.write().unwrap().add_configurator(positions);
+    let mut ecs = EcsContainer::create().add_module(&POSITIONS).seal();
+    ecs.module_switch(&POSITIONS).unwrap().disable();
+
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(WorldPosition { x: 1 }).key()
+    });
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity)
+            .unwrap()
+            .modify::<WorldPosition>(|it| it.x = 200);
+    });
+
+    assert_eq!(get(&mut ecs, entity), Some(WorldPosition { x: 200 }));
+    assert_eq!(get(&mut ecs, entity), Some(Visible { on_screen: false }));
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Fuel {
+    amount: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Range {
+    distance: i32,
+}
+
+#[test]
+fn recomputation_caused_by_modification() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_derived_component("range", |fuel: &Fuel| {
+                assert!(fuel.amount >= 0, "report cause");
+                Range {
+                    distance: fuel.amount * 10,
+                }
+            });
+        })
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Fuel { amount: 1 }).key()
+    });
+    let entity = entity.unwrap();
+
+    let (_, result) = ecs.execute_once("drain_fuel_by_player", move |ctx| {
+        ctx.get_entity(entity)
+            .unwrap()
+            .modify::<Fuel>(|it| it.amount = -1);
+    });
+
+    assert_eq!(result.errors.len(), 1);
+    let cause = format!("{}", result.errors[0].cause);
+    assert!(cause.contains("range"), "{}", cause);
+    assert!(cause.contains("drain_fuel_by_player"), "{}", cause);
+}
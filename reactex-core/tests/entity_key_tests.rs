@@ -0,0 +1,111 @@
+use reactex_core::EcsContainer;
+use reactex_core::EntityError;
+use reactex_core::EntityKey;
+use reactex_macro::EcsComponent;
+use std::collections::BTreeMap;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+fn create_entities(ecs: &mut EcsContainer, count: usize) -> Vec<EntityKey> {
+    let (keys, _) = ecs.execute_once("test", move |ctx| {
+        (0..count)
+            .map(|it| ctx.create_entity().add(A { value: it as i32 }).key())
+            .collect::<Vec<_>>()
+    });
+    keys.unwrap()
+}
+
+#[test]
+fn key_restored_from_bits() {
+    let mut ecs = EcsContainer::create().seal();
+    let keys = create_entities(&mut ecs, 3);
+
+    for key in keys {
+        assert_eq!(EntityKey::from_bits(key.to_bits()).unwrap(), key);
+    }
+}
+
+#[test]
+fn key_restored_from_bits_is_usable() {
+    let mut ecs = EcsContainer::create().seal();
+    let key = create_entities(&mut ecs, 1)[0];
+    let bits = key.to_bits();
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(EntityKey::from_bits(bits).unwrap())
+            .unwrap()
+            .get::<A>()
+            .cloned()
+    });
+
+    assert_eq!(result, Some(Some(A { value: 0 })));
+}
+
+#[test]
+fn stale_key_restored_from_bits_is_not_valid() {
+    let mut ecs = EcsContainer::create().seal();
+    let key = create_entities(&mut ecs, 1)[0];
+    ecs.execute_once("test", move |ctx| ctx.get_entity(key).unwrap().destroy());
+    create_entities(&mut ecs, 1);
+    let bits = key.to_bits();
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(EntityKey::from_bits(bits).unwrap()).is_some()
+    });
+
+    assert_eq!(result, Some(false));
+}
+
+#[test]
+fn corrupted_bits_rejected() {
+    let mut ecs = EcsContainer::create().seal();
+    let key = create_entities(&mut ecs, 1)[0];
+
+    let result = EntityKey::from_bits(key.to_bits() | 1 << 16);
+
+    assert_eq!(result, Err(EntityError::NotExists));
+}
+
+#[test]
+fn entities_created_at_once_beyond_initial_capacity() {
+    let mut ecs = EcsContainer::create().seal();
+    // shifts the following entities off the power of two boundaries
+    create_entities(&mut ecs, 1);
+    let keys = create_entities(&mut ecs, 2_000);
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        keys.iter()
+            .map(|it| ctx.get_entity(*it).unwrap().get::<A>().unwrap().value)
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(result, Some((0..2_000).collect::<Vec<_>>()));
+}
+
+#[test]
+fn keys_usable_in_btree_map() {
+    let mut ecs = EcsContainer::create().seal();
+    let keys = create_entities(&mut ecs, 3);
+
+    let map: BTreeMap<_, _> = keys.iter().rev().map(|it| (*it, it.to_bits())).collect();
+
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), sorted);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn key_serialized() {
+    let mut ecs = EcsContainer::create().seal();
+    let key = create_entities(&mut ecs, 1)[0];
+
+    let json = serde_json::to_string(&key).unwrap();
+
+    assert_eq!(json, key.to_bits().to_string());
+    assert_eq!(serde_json::from_str::<EntityKey>(&json).unwrap(), key);
+    assert!(serde_json::from_str::<EntityKey>(&u64::MAX.to_string()).is_err());
+}
@@ -293,3 +293,41 @@ fn destroyed_entity_doesnt_fire_similar_disappears() {
     world.execute_all();
     assert_eq!(matched.lock().unwrap().deref(), &vec! {});
 }
+
+#[test]
+fn modification_made_by_appear_handler_flushed_in_the_same_run() {
+    let mut world = ConfigurableWorld::create_for_test();
+    world.add_appear_handler("test", ecs_filter!(C), |ctx, entity| {
+        ctx.get_entity(entity)
+            .unwrap()
+            .modify::<C>(|it| it.value += 1);
+    });
+    let mut world = world.seal();
+    let eC = world.create_entity();
+    world.add_component(eC, C { value: 7 }).unwrap();
+    world.execute_all();
+    assert_eq!(world.get_component::<C>(eC).unwrap(), Some(&C { value: 8 }));
+}
+
+#[test]
+fn component_removed_by_disappear_handler_of_destroyed_entity_disappears_once() {
+    let matched = Rc::new(Mutex::new(Vec::new()));
+    let mut world = ConfigurableWorld::create_for_test();
+    world.add_disappear_handler("remove", ecs_filter!(A), |ctx, entity| {
+        ctx.get_entity(entity).unwrap().remove::<B>();
+    });
+    {
+        let matched = matched.clone();
+        world.add_disappear_handler("test", ecs_filter!(B), move |_, entity| {
+            matched.lock().unwrap().push(entity)
+        });
+    }
+    let mut world = world.seal();
+    let eA = world.create_entity();
+    world.add_component(eA, A {}).unwrap();
+    world.add_component(eA, B {}).unwrap();
+    world.execute_all();
+    world.destroy_entity(eA).unwrap();
+    world.execute_all();
+    assert_eq!(matched.lock().unwrap().deref(), &vec! {eA});
+}
@@ -0,0 +1,123 @@
+use reactex_core::EcsComponent;
+use reactex_core::EcsContainer;
+use reactex_macro::register_component;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+enum Team {
+    Red,
+    Blue { score: i32 },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Player;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Enemy;
+
+static ADDED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+#[ecs(on_add = |_, _| ADDED.lock().unwrap().push(<Self as EcsComponent>::NAME))]
+struct Health<T: Send + Sync + 'static> {
+    hp: i32,
+    marker: PhantomData<T>,
+}
+
+impl<T: Send + Sync + 'static> Health<T> {
+    fn new(hp: i32) -> Self {
+        Health {
+            hp,
+            marker: PhantomData,
+        }
+    }
+}
+
+register_component!(Health<Player>);
+register_component!(Health<Enemy>);
+
+#[derive(EcsComponent, Debug)]
+struct Tagged<T: Send + Sync + 'static> {
+    #[ecs(index)]
+    tag: u32,
+    marker: PhantomData<T>,
+}
+
+register_component!(Tagged<Player>);
+
+#[test]
+fn enum_component_added() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Team::Blue { score: 3 }).key()
+    });
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity.unwrap()).unwrap().get::<Team>().cloned()
+    });
+
+    assert_eq!(result, Some(Some(Team::Blue { score: 3 })));
+    assert_ne!(Team::Red, Team::Blue { score: 0 });
+}
+
+#[test]
+fn generic_component_monomorphizations_are_distinct() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(Health::<Player>::new(10))
+            .add(Health::<Enemy>::new(20))
+            .key()
+    });
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity.unwrap()).unwrap();
+        (
+            entity.get::<Health<Player>>().unwrap().hp,
+            entity.get::<Health<Enemy>>().unwrap().hp,
+        )
+    });
+
+    assert_eq!(result, Some((10, 20)));
+    assert_ne!(
+        Health::<Player>::get_component_type(),
+        Health::<Enemy>::get_component_type()
+    );
+}
+
+#[test]
+fn generic_component_attributes_registered() {
+    let mut ecs = EcsContainer::create().seal();
+
+    ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Health::<Enemy>::new(1));
+    });
+
+    assert!(ADDED
+        .lock()
+        .unwrap()
+        .contains(&<Health<Enemy> as EcsComponent>::NAME));
+    assert!(<Health<Enemy> as EcsComponent>::NAME.ends_with("::Health<Enemy>"));
+}
+
+#[test]
+fn generic_component_indexed() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(Tagged::<Player> {
+                tag: 7,
+                marker: PhantomData,
+            })
+            .key()
+    });
+
+    let (found, _) = ecs.execute_once("lookup", |ctx| {
+        ctx.lookup::<Tagged<Player>>(&7)
+            .map(|it| it.key())
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(found.unwrap(), vec![entity.unwrap()]);
+}
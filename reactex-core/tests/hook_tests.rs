@@ -0,0 +1,94 @@
+use reactex_core::Ctx;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_macro::EcsComponent;
+use std::sync::Mutex;
+
+static GPU_HANDLES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+#[derive(EcsComponent, Debug)]
+#[ecs(on_add = acquire_handle, on_remove = release_handle)]
+struct Texture {
+    handle: u32,
+}
+
+fn acquire_handle(ctx: Ctx, entity: EntityKey) {
+    let handle = ctx.get_entity(entity).unwrap().get::<Texture>().unwrap().handle;
+    GPU_HANDLES.lock().unwrap().push(handle);
+}
+
+fn release_handle(ctx: Ctx, entity: EntityKey) {
+    let handle = ctx.get_entity(entity).unwrap().get::<Texture>().unwrap().handle;
+    GPU_HANDLES.lock().unwrap().retain(|it| *it != handle);
+}
+
+#[derive(EcsComponent, Debug)]
+#[ecs(on_add = |ctx, entity| {
+    ctx.get_entity(entity).unwrap().add(Registered {});
+})]
+struct Named {}
+
+#[derive(EcsComponent, Debug)]
+struct Registered {}
+
+fn handles() -> Vec<u32> {
+    GPU_HANDLES.lock().unwrap().clone()
+}
+
+#[test]
+fn hooks_invoked_on_component_add_and_remove() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Texture { handle: 101 }).key()
+    });
+    assert!(handles().contains(&101));
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity.unwrap()).unwrap().remove::<Texture>();
+    });
+
+    assert!(!handles().contains(&101));
+}
+
+#[test]
+fn remove_hook_invoked_on_entity_destroy() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Texture { handle: 102 }).key()
+    });
+    assert!(handles().contains(&102));
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity.unwrap()).unwrap().destroy();
+    });
+
+    assert!(!handles().contains(&102));
+}
+
+#[test]
+fn hook_changes_applied() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(Named {}).key());
+
+    let (registered, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity.unwrap()).unwrap().get::<Registered>().is_some()
+    });
+
+    assert_eq!(registered, Some(true));
+}
+
+#[test]
+fn hooks_invoked_in_every_container() {
+    let mut first = EcsContainer::create().seal();
+    let mut second = EcsContainer::create().seal();
+
+    first.execute_once("test", |ctx| {
+        ctx.create_entity().add(Texture { handle: 103 });
+    });
+    second.execute_once("test", |ctx| {
+        ctx.create_entity().add(Texture { handle: 104 });
+    });
+
+    assert!(handles().contains(&103));
+    assert!(handles().contains(&104));
+}
@@ -0,0 +1,166 @@
+use reactex_core::EcsContainer;
+use reactex_core::EcsIndexed;
+use reactex_core::EntityKey;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug)]
+struct PlayerId(#[ecs(index(unique))] u32);
+
+#[derive(EcsComponent, Debug)]
+struct GridCell {
+    #[ecs(index)]
+    cell: (i32, i32),
+    layer: u8,
+}
+
+#[derive(EcsComponent, Debug)]
+struct Name {
+    value: String,
+}
+
+impl EcsIndexed for Name {
+    type IndexKey = String;
+}
+
+fn lookup_players(ecs: &mut EcsContainer, id: u32) -> Vec<EntityKey> {
+    let (result, _) = ecs.execute_once("lookup", move |ctx| {
+        ctx.lookup::<PlayerId>(&id).map(|it| it.key()).collect()
+    });
+    result.unwrap()
+}
+
+fn lookup_cells(ecs: &mut EcsContainer, cell: (i32, i32)) -> Vec<EntityKey> {
+    let (result, _) = ecs.execute_once("lookup", move |ctx| {
+        let mut entities: Vec<_> = ctx.lookup::<GridCell>(&cell).map(|it| it.key()).collect();
+        entities.sort_by_key(|it| it.to_string());
+        entities
+    });
+    result.unwrap()
+}
+
+fn layers(ecs: &mut EcsContainer, cell: (i32, i32)) -> Vec<u8> {
+    let (result, _) = ecs.execute_once("lookup", move |ctx| {
+        let mut layers: Vec<_> = ctx
+            .lookup::<GridCell>(&cell)
+            .map(|it| it.get::<GridCell>().unwrap().layer)
+            .collect();
+        layers.sort();
+        layers
+    });
+    result.unwrap()
+}
+
+#[test]
+fn entities_found_by_indexed_field() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entities, _) = ecs.execute_once("test", |ctx| {
+        let mut cell = vec![
+            ctx.create_entity()
+                .add(GridCell { cell: (1, 2), layer: 0 })
+                .key(),
+            ctx.create_entity()
+                .add(GridCell { cell: (1, 2), layer: 1 })
+                .key(),
+        ];
+        cell.sort_by_key(|it| it.to_string());
+        ctx.create_entity().add(GridCell { cell: (3, 4), layer: 0 });
+        let player = ctx.create_entity().add(PlayerId(42)).key();
+        (cell, player)
+    });
+    let (cell, player) = entities.unwrap();
+
+    assert_eq!(lookup_cells(&mut ecs, (1, 2)), cell);
+    assert_eq!(layers(&mut ecs, (1, 2)), vec![0, 1]);
+    assert_eq!(lookup_players(&mut ecs, 42), vec![player]);
+    assert!(lookup_players(&mut ecs, 7).is_empty());
+}
+
+#[test]
+fn index_updated_on_modification_and_removal() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entities, _) = ecs.execute_once("test", |ctx| {
+        [
+            ctx.create_entity().add(PlayerId(1)).key(),
+            ctx.create_entity().add(PlayerId(2)).key(),
+        ]
+    });
+    let [first, second] = entities.unwrap();
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(first).unwrap().modify::<PlayerId>(|it| it.0 = 10);
+        ctx.get_entity(second).unwrap().remove::<PlayerId>();
+    });
+
+    assert!(lookup_players(&mut ecs, 1).is_empty());
+    assert!(lookup_players(&mut ecs, 2).is_empty());
+    assert_eq!(lookup_players(&mut ecs, 10), vec![first]);
+}
+
+#[test]
+fn destroyed_entity_unindexed() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(PlayerId(5)).key());
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity.unwrap()).unwrap().destroy();
+    });
+
+    assert!(lookup_players(&mut ecs, 5).is_empty());
+}
+
+#[test]
+fn unique_index_violation_reported() {
+    let mut ecs = EcsContainer::create().seal();
+    ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(PlayerId(3));
+    });
+
+    let (_, result) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(PlayerId(3));
+    });
+
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0]
+        .details
+        .message
+        .contains("unique index of"));
+    assert_eq!(lookup_players(&mut ecs, 3).len(), 2);
+}
+
+#[test]
+fn index_registered_explicitly() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.register_index::<Name>(|it| it.value.to_lowercase());
+        })
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(Name {
+                value: "Alice".to_string(),
+            })
+            .key()
+    });
+
+    let (found, _) = ecs.execute_once("lookup", |ctx| {
+        ctx.lookup::<Name>(&"alice".to_string())
+            .map(|it| it.key())
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(found.unwrap(), vec![entity.unwrap()]);
+}
+
+#[test]
+fn lookup_key_type_inferred_from_indexed_field() {
+    let mut ecs = EcsContainer::create().seal();
+    let (player, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(PlayerId(42)).key());
+
+    let (found, _) = ecs.execute_once("lookup", |ctx| {
+        ctx.lookup::<PlayerId>(&42)
+            .map(|it| it.key())
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(found.unwrap(), vec![player.unwrap()]);
+}
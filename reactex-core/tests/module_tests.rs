@@ -0,0 +1,147 @@
+mod common;
+
+use common::log;
+use common::take_events;
+use common::tick;
+use common::Tick;
+use reactex_core::ecs_module;
+use reactex_core::on_signal_global;
+use reactex_core::Ctx;
+use reactex_core::EcsContainer;
+
+ecs_module!(PHYSICS);
+ecs_module!(AI);
+ecs_module!(TUNED_AI);
+ecs_module!(EMPTY);
+ecs_module!(BASE);
+ecs_module!(GAME);
+ecs_module!(LEFT);
+ecs_module!(RIGHT);
+ecs_module!(SHARED_CONFIG);
+ecs_module!(HOST);
+ecs_module!(PLUGIN);
+ecs_module!(UNLOADED);
+
+struct AiConfig {
+    aggression: u32,
+}
+
+struct PluginConfig {
+    volume: u32,
+}
+
+#[on_signal_global(PHYSICS)]
+fn integrate(_ctx: Ctx<Tick>) {
+    log("integrate");
+}
+
+#[on_signal_global(AI)]
+fn think(_ctx: Ctx<Tick>) {
+    log("think");
+}
+
+#[on_signal_global(TUNED_AI)]
+fn attack(ctx: Ctx<Tick>) {
+    log(format!(
+        "attack, aggression {}",
+        ctx.module_config::<AiConfig>().aggression
+    ));
+}
+
+#[on_signal_global(SHARED_CONFIG)]
+fn defend(ctx: Ctx<Tick>) {
+    log(format!(
+        "defend, aggression {}",
+        ctx.module_config::<AiConfig>().aggression
+    ));
+}
+
+#[test]
+fn dependency_added_before_module() {
+    take_events();
+    AI.write().unwrap().depends_on(&PHYSICS);
+    let mut ecs = EcsContainer::create().add_module(&AI).seal();
+
+    tick(&mut ecs);
+
+    assert_eq!(take_events(), vec!["integrate", "think"]);
+    assert!(ecs.module_switch(&PHYSICS).is_some());
+    assert_eq!(AI.read().unwrap().name(), "AI");
+}
+
+#[test]
+fn module_config_readable_by_handlers() {
+    take_events();
+    let mut ecs = EcsContainer::create()
+        .add_module_with(&TUNED_AI, AiConfig { aggression: 3 })
+        .seal();
+
+    tick(&mut ecs);
+
+    assert_eq!(take_events(), vec!["attack, aggression 3"]);
+}
+
+#[test]
+#[should_panic(expected = "module EMPTY is added twice")]
+fn module_added_twice_rejected() {
+    EcsContainer::create().add_module(&EMPTY).add_module(&EMPTY);
+}
+
+#[test]
+fn module_added_after_it_was_added_as_dependency() {
+    GAME.write().unwrap().depends_on(&BASE);
+    let ecs = EcsContainer::create()
+        .add_module(&GAME)
+        .add_module(&BASE)
+        .seal();
+
+    assert!(ecs.module_switch(&BASE).is_some());
+}
+
+#[test]
+#[should_panic(expected = "module dependency cycle: LEFT -> RIGHT -> LEFT")]
+fn dependency_cycle_rejected() {
+    LEFT.write().unwrap().depends_on(&RIGHT);
+    RIGHT.write().unwrap().depends_on(&LEFT);
+    EcsContainer::create().add_module(&LEFT);
+}
+
+#[test]
+fn modules_read_own_configs_of_shared_type() {
+    take_events();
+    let mut ecs = EcsContainer::create()
+        .add_module_with(&TUNED_AI, AiConfig { aggression: 3 })
+        .add_module_with(&SHARED_CONFIG, AiConfig { aggression: 5 })
+        .seal();
+
+    tick(&mut ecs);
+
+    let mut events = take_events();
+    events.sort();
+    assert_eq!(events, vec!["attack, aggression 3", "defend, aggression 5"]);
+}
+
+#[test]
+#[should_panic(expected = "module HOST is required by PLUGIN")]
+fn module_removal_rejected_while_dependents_remain() {
+    PLUGIN.write().unwrap().depends_on(&HOST);
+    let mut ecs = EcsContainer::create().add_module(&PLUGIN).seal();
+
+    ecs.remove_module(&HOST);
+}
+
+#[test]
+fn removed_module_config_dropped() {
+    let mut ecs = EcsContainer::create()
+        .add_module_with(&UNLOADED, PluginConfig { volume: 7 })
+        .seal();
+    let (volume, _) = ecs.execute_once("read", |ctx| ctx.module_config::<PluginConfig>().volume);
+    assert_eq!(volume, Some(7));
+
+    ecs.remove_module(&UNLOADED);
+
+    let (volume, result) =
+        ecs.execute_once("read", |ctx| ctx.module_config::<PluginConfig>().volume);
+    assert_eq!(volume, None);
+    assert_eq!(result.errors.len(), 1);
+}
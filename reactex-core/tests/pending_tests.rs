@@ -0,0 +1,227 @@
+use reactex_core::EcsContainer;
+use reactex_macro::EcsComponent;
+use std::cell::RefCell;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct W {
+    a: i32,
+    b: i32,
+}
+
+#[test]
+fn pending_addition_visible_in_the_same_handler() {
+    let mut ecs = EcsContainer::create().seal();
+    let (result, _) = ecs.execute_once("test", |ctx| {
+        let entity = ctx.create_entity().add(A { value: 42 });
+        (entity.has_pending::<A>(), entity.get_pending::<A>())
+    });
+    assert_eq!(result, Some((true, Some(A { value: 42 }))));
+}
+
+#[test]
+fn pending_addition_to_committed_entity_visible() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().key());
+    let entity = entity.unwrap();
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.add(A { value: 42 });
+        (entity.get::<A>().is_some(), entity.get_pending::<A>())
+    });
+    assert_eq!(result, Some((false, Some(A { value: 42 }))));
+}
+
+#[test]
+fn pending_removal_visible_in_the_same_handler() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(A { value: 1 }).key());
+    let entity = entity.unwrap();
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.remove::<A>();
+        (entity.get::<A>().is_some(), entity.has_pending::<A>())
+    });
+    assert_eq!(result, Some((true, false)));
+}
+
+#[test]
+fn pending_destroy_hides_components() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(A { value: 1 }).key());
+    let entity = entity.unwrap();
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.destroy();
+        entity.get_pending::<A>()
+    });
+    assert_eq!(result, Some(None));
+}
+
+#[test]
+fn pending_modifications_previewed() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(W { a: 17, b: 42 }).key()
+    });
+    let entity = entity.unwrap();
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.modify::<W>(|it| it.a += 1);
+        entity.modify::<W>(|it| it.b += 3);
+        (entity.get::<W>().cloned(), entity.get_pending::<W>())
+    });
+    assert_eq!(
+        result,
+        Some((Some(W { a: 17, b: 42 }), Some(W { a: 18, b: 45 })))
+    );
+}
+
+#[test]
+fn previewed_modifications_committed_once() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(W { a: 17, b: 42 }).key()
+    });
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.modify::<W>(|it| it.a += 1);
+        entity.get_pending::<W>();
+        entity.modify::<W>(|it| it.b += 3);
+        entity.get_pending::<W>();
+    });
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().get::<W>().cloned()
+    });
+    assert_eq!(result, Some(Some(W { a: 18, b: 45 })));
+}
+
+#[test]
+fn pending_set_previewed() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(W { a: 17, b: 42 }).key()
+    });
+    let entity = entity.unwrap();
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.set(W { a: 1, b: 2 });
+        entity.modify::<W>(|it| it.b += 3);
+        entity.get_pending::<W>()
+    });
+    assert_eq!(result, Some(Some(W { a: 1, b: 5 })));
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().get::<W>().cloned()
+    });
+    assert_eq!(result, Some(Some(W { a: 1, b: 5 })));
+}
+
+#[test]
+fn scoped_modification_may_borrow_locals() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(W { a: 17, b: 42 }).key()
+    });
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        let delta = [1, 3];
+        entity.modify_scoped::<W>(|it| it.a += delta[0]);
+        entity.modify_scoped::<W>(|it| it.b += delta[1]);
+    });
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().get::<W>().cloned()
+    });
+    assert_eq!(result, Some(Some(W { a: 18, b: 45 })));
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Sum {
+    value: i32,
+}
+
+thread_local! {
+    static DERIVED: RefCell<Vec<i32>> = const { RefCell::new(Vec::new()) };
+}
+
+// modifications, sets and a preview in between, returns the committed value and what the
+// modify handlers observed
+fn commit_interleaved_changes(preview: bool) -> (Option<W>, Vec<i32>) {
+    DERIVED.with(|it| it.take());
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_derived_component("sum", |w: &W| {
+                DERIVED.with(|it| it.borrow_mut().push(w.a + w.b));
+                Sum { value: w.a + w.b }
+            });
+        })
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(W { a: 17, b: 42 }).key()
+    });
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.modify::<W>(|it| it.a += 1);
+        if preview {
+            entity.get_pending::<W>();
+        }
+        entity.set(W { a: 1, b: 2 });
+        entity.modify::<W>(|it| it.b *= 10);
+        if preview {
+            entity.get_pending::<W>();
+        }
+        entity.modify::<W>(|it| it.a -= 5);
+    });
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().get::<W>().cloned()
+    });
+    (result.unwrap(), DERIVED.with(|it| it.take()))
+}
+
+#[test]
+fn preview_does_not_change_committed_changes() {
+    let expected = (Some(W { a: -4, b: 20 }), vec![59, 16]);
+    assert_eq!(commit_interleaved_changes(false), expected);
+    assert_eq!(commit_interleaved_changes(true), expected);
+}
+
+#[test]
+fn many_scoped_modifications_committed() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entities, _) = ecs.execute_once("test", |ctx| {
+        (0..100)
+            .map(|_| ctx.create_entity().add(W { a: 0, b: 0 }).key())
+            .collect::<Vec<_>>()
+    });
+    let entities = entities.unwrap();
+    let keys = entities.clone();
+    ecs.execute_once("test", move |ctx| {
+        let steps = [1, 2];
+        for entity in &keys {
+            let entity = ctx.get_entity(*entity).unwrap();
+            for _ in 0..1000 {
+                entity.modify_scoped::<W>(|it| it.a += steps[0]);
+            }
+        }
+        // interleaved with changes of other entities
+        for _ in 0..10 {
+            for entity in &keys {
+                let entity = ctx.get_entity(*entity).unwrap();
+                entity.modify_scoped::<W>(|it| it.b += steps[1]);
+            }
+        }
+    });
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        entities
+            .iter()
+            .map(|it| ctx.get_entity(*it).unwrap().get::<W>().cloned().unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(result.unwrap(), vec![W { a: 1000, b: 20 }; 100]);
+}
@@ -0,0 +1,221 @@
+#![cfg(feature = "prefab")]
+
+mod common;
+
+use common::get;
+use common::has;
+use reactex_core::EcsContainer;
+use reactex_core::Prefab;
+use reactex_core::PrefabError;
+use reactex_core::PrefabOverrides;
+use reactex_core::WorldError;
+use reactex_macro::EcsComponent;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(EcsComponent, Deserialize, Debug, Clone, Eq, PartialEq)]
+struct Health {
+    hp: i32,
+    max: i32,
+}
+
+#[derive(EcsComponent, Deserialize, Debug, Clone, Eq, PartialEq)]
+struct Hostile;
+
+#[derive(EcsComponent, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+enum Faction {
+    Neutral,
+    Clan { id: u32 },
+}
+
+#[derive(EcsComponent, Deserialize, Debug, Clone, Eq, PartialEq)]
+struct Loot {
+    drops: HashMap<u32, (String, u32)>,
+    faction: Faction,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct NotData {
+    value: i32,
+}
+
+const GOBLIN_RON: &str = r#"
+{
+    "goblin": {
+        "Health": Health(hp: 10, max: 10),
+        "Hostile": (),
+    },
+}
+"#;
+
+const GOBLIN_JSON: &str = r#"
+{
+    "goblin": {
+        "Health": { "hp": 10, "max": 10 },
+        "Hostile": null
+    }
+}
+"#;
+
+#[test]
+fn prefab_spawned_from_ron() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| world.load_prefabs_ron(GOBLIN_RON).unwrap())
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.spawn_prefab("goblin").unwrap().key());
+
+    let entity = entity.unwrap();
+
+    assert_eq!(get(&mut ecs, entity), Some(Health { hp: 10, max: 10 }));
+    assert!(has::<Hostile>(&mut ecs, entity));
+    assert!(!has::<NotData>(&mut ecs, entity));
+}
+
+#[test]
+fn prefab_spawned_from_json() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| world.load_prefabs_json(GOBLIN_JSON).unwrap())
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.spawn_prefab("goblin").unwrap().key());
+
+    let entity = entity.unwrap();
+
+    assert_eq!(get(&mut ecs, entity), Some(Health { hp: 10, max: 10 }));
+    assert!(has::<Hostile>(&mut ecs, entity));
+    assert!(!has::<NotData>(&mut ecs, entity));
+}
+
+#[test]
+fn prefab_built_in_code() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_prefab(
+                "boss",
+                Prefab::new()
+                    .with(Health { hp: 100, max: 100 })
+                    .with(NotData { value: 42 }),
+            )
+        })
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.spawn_prefab("boss").unwrap().key());
+
+    let entity = entity.unwrap();
+
+    assert_eq!(get(&mut ecs, entity), Some(Health { hp: 100, max: 100 }));
+    assert!(!has::<Hostile>(&mut ecs, entity));
+    assert_eq!(get(&mut ecs, entity), Some(NotData { value: 42 }));
+}
+
+#[test]
+fn prefab_fields_overridden() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| world.load_prefabs_ron(GOBLIN_RON).unwrap())
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        let overrides = PrefabOverrides::new().set("Health", "hp", 3).unwrap();
+        ctx.spawn_prefab_with("goblin", &overrides).unwrap().key()
+    });
+
+    let entity = entity.unwrap();
+
+    assert_eq!(get(&mut ecs, entity), Some(Health { hp: 3, max: 10 }));
+    assert!(has::<Hostile>(&mut ecs, entity));
+}
+
+#[test]
+fn prefab_spawned_components_visible_as_pending() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| world.load_prefabs_ron(GOBLIN_RON).unwrap())
+        .seal();
+    let (result, _) = ecs.execute_once("test", |ctx| {
+        ctx.spawn_prefab("goblin").unwrap().get_pending::<Health>()
+    });
+
+    assert_eq!(result, Some(Some(Health { hp: 10, max: 10 })));
+}
+
+#[test]
+fn unknown_prefab_not_spawned() {
+    let mut ecs = EcsContainer::create().seal();
+    let (result, _) = ecs.execute_once("test", |ctx| ctx.spawn_prefab("orc").err());
+
+    assert_eq!(result, Some(Some(PrefabError::NotFound("orc".to_owned()))));
+}
+
+#[test]
+fn prefab_with_unknown_component_not_loaded() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            let result = world.load_prefabs_ron(r#"{ "goblin": { "NotData": (value: 1) } }"#);
+            assert_eq!(
+                result,
+                Err(PrefabError::UnknownComponent("NotData".to_owned()))
+            );
+        })
+        .seal();
+    let (result, _) = ecs.execute_once("test", |ctx| ctx.spawn_prefab("goblin").is_ok());
+
+    assert_eq!(result, Some(false));
+}
+
+#[test]
+fn prefab_with_invalid_component_not_loaded() {
+    EcsContainer::create().configure_in_test(|world| {
+        let result = world.load_prefabs_json(r#"{ "goblin": { "Health": { "hp": 1 } } }"#);
+        assert!(matches!(result, Err(PrefabError::InvalidComponent(..))));
+    });
+}
+
+#[test]
+fn ron_enums_tuples_and_maps_deserialized_directly() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world
+                .load_prefabs_ron(
+                    r#"{
+                        "chief": {
+                            "Faction": Clan(id: 7),
+                            "Loot": (drops: {1: ("axe", 2)}, faction: Neutral),
+                        },
+                    }"#,
+                )
+                .unwrap()
+        })
+        .seal();
+    let (result, _) = ecs.execute_once("test", |ctx| {
+        let entity = ctx
+            .spawn_prefab_with(
+                "chief",
+                &PrefabOverrides::new()
+                    .set("Loot", "faction", Faction::Clan { id: 1 })
+                    .unwrap(),
+            )
+            .unwrap();
+        (
+            entity.get_pending::<Faction>(),
+            entity.get_pending::<Loot>(),
+        )
+    });
+
+    assert_eq!(
+        result,
+        Some((
+            Some(Faction::Clan { id: 7 }),
+            Some(Loot {
+                drops: HashMap::from([(1, ("axe".to_owned(), 2))]),
+                faction: Faction::Clan { id: 1 },
+            })
+        ))
+    );
+}
+
+#[test]
+fn unserializable_override_rejected() {
+    let result = PrefabOverrides::new().set("Loot", "drops", HashMap::from([((1, 2), 3)]));
+
+    assert!(matches!(
+        result,
+        Err(WorldError::Prefab(PrefabError::InvalidOverride(..)))
+    ));
+}
@@ -2,6 +2,7 @@
 
 use reactex_core::ecs_filter;
 use reactex_core::ConfigurableWorld;
+use reactex_core::QueryError;
 use reactex_core::World;
 use reactex_macro::EcsComponent;
 use to_vec::ToVec;
@@ -160,6 +161,85 @@ fn AMatchesEmpty() {
     assert_eq!(matched, vec![eA]);
 }
 
+#[test]
+fn CountMatchesCommittedEntities() {
+    let query_A = ecs_filter!(A);
+    World::register_query(query_A);
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let e1 = world.create_entity();
+    world.add_component(e1, A::default()).unwrap();
+    let e2 = world.create_entity();
+    world.add_component(e2, A::default()).unwrap();
+    world.create_entity();
+
+    assert_eq!(world.query_count(query_A), 0);
+    assert!(world.query_is_empty(query_A));
+
+    world.execute_all();
+
+    assert_eq!(world.query_count(query_A), 2);
+    assert!(!world.query_is_empty(query_A));
+}
+
+#[test]
+fn SingleFailsIfNothingMatched() {
+    let query_B = ecs_filter!(B);
+    World::register_query(query_B);
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let eA = world.create_entity();
+    world.add_component(eA, A::default()).unwrap();
+    world.execute_all();
+
+    assert_eq!(world.query_single(query_B), Err(QueryError::NotFound));
+    assert_eq!(world.query_first(query_B), None);
+}
+
+#[test]
+fn SingleFailsIfManyMatched() {
+    let query_B = ecs_filter!(B);
+    World::register_query(query_B);
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let e1 = world.create_entity();
+    world.add_component(e1, B::default()).unwrap();
+    let e2 = world.create_entity();
+    world.add_component(e2, B::default()).unwrap();
+    world.execute_all();
+
+    assert_eq!(world.query_single(query_B), Err(QueryError::NotUnique));
+    assert!(world.query_first(query_B).is_some());
+}
+
+#[test]
+fn SingleReturnsTheOnlyMatch() {
+    let query_AB = ecs_filter!(A, B);
+    World::register_query(query_AB);
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let eA = world.create_entity();
+    world.add_component(eA, A::default()).unwrap();
+    let eAB = world.create_entity();
+    world.add_component(eAB, A::default()).unwrap();
+    world.add_component(eAB, B::default()).unwrap();
+    world.execute_all();
+
+    assert_eq!(world.query_single(query_AB), Ok(eAB));
+    assert_eq!(world.query_first(query_AB), Some(eAB));
+}
+
+#[test]
+fn ContainsOnlyMatched() {
+    let query_A = ecs_filter!(A);
+    World::register_query(query_A);
+    let mut world = ConfigurableWorld::create_for_test().seal();
+    let eA = world.create_entity();
+    world.add_component(eA, A::default()).unwrap();
+    let eB = world.create_entity();
+    world.add_component(eB, B::default()).unwrap();
+    world.execute_all();
+
+    assert!(world.query_contains(query_A, eA));
+    assert!(!world.query_contains(query_A, eB));
+}
+
 /*
 
 */
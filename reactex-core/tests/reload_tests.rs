@@ -0,0 +1,205 @@
+mod common;
+
+use common::log;
+use common::take_events;
+use common::tick;
+use common::Tick;
+use reactex_core::ecs_filter;
+use reactex_core::ecs_module;
+use reactex_core::ConfigurableWorld;
+use reactex_core::EcsAggregate;
+use reactex_core::EcsComponent;
+use reactex_core::EcsContainer;
+use reactex_core::EcsIndexed;
+use std::cell::Cell;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+ecs_module!(GREETER);
+ecs_module!(GUARDS);
+ecs_module!(CENSUS);
+ecs_module!(RETIRED_CENSUS);
+ecs_module!(FLAKY);
+
+#[derive(EcsComponent, Debug)]
+struct Guard {}
+
+#[derive(EcsComponent, Debug)]
+struct Rank {
+    value: u32,
+}
+
+impl EcsIndexed for Rank {
+    type IndexKey = u32;
+}
+
+#[derive(Default, Debug)]
+struct GuardCount(usize);
+
+impl EcsAggregate for GuardCount {
+    type Item = ();
+
+    fn add(&mut self, _: &()) {
+        self.0 += 1;
+    }
+
+    fn remove(&mut self, _: &()) -> bool {
+        self.0 -= 1;
+        true
+    }
+}
+
+thread_local! {
+    static BROKEN: Cell<bool> = const { Cell::new(false) };
+}
+
+fn greet_v1(world: &mut ConfigurableWorld) {
+    world.add_global_signal_handler::<Tick>("greet", |_| log("hello v1"));
+}
+
+fn greet_v2(world: &mut ConfigurableWorld) {
+    world.add_global_signal_handler::<Tick>("greet", |_| log("hello v2"));
+    world.add_entity_signal_handler::<Tick>("salute", ecs_filter!(Guard), |_, _| log("salute"));
+}
+
+fn guard(world: &mut ConfigurableWorld) {
+    world.add_appear_handler("guard appeared", ecs_filter!(Guard), |_, _| log("appear"));
+    world.add_entity_signal_handler::<Tick>("patrol", ecs_filter!(Guard), |_, _| log("patrol"));
+}
+
+fn census(world: &mut ConfigurableWorld) {
+    world.add_aggregate::<GuardCount, Guard>(ecs_filter!(Guard), |_| ());
+    world.register_index::<Rank>(|it| it.value);
+}
+
+fn flaky(world: &mut ConfigurableWorld) {
+    world.add_entity_signal_handler::<Tick>("patrol", ecs_filter!(Guard), |_, _| log("patrol"));
+    if BROKEN.get() {
+        panic!("broken configurator");
+    }
+}
+
+fn spawn_guard(ecs: &mut EcsContainer, rank: u32) {
+    ecs.execute_once("spawn", move |ctx| {
+        ctx.create_entity().add(Guard {}).add(Rank { value: rank });
+    });
+}
+
+// guard count and number of guards of the rank
+fn census_of(ecs: &mut EcsContainer, rank: u32) -> (usize, usize) {
+    let (result, _) = ecs.execute_once("census", move |ctx| {
+        (
+            ctx.aggregate::<GuardCount>().0,
+            ctx.lookup::<Rank>(&rank).count(),
+        )
+    });
+    result.unwrap()
+}
+
+#[test]
+fn module_replaced_with_reloaded_handlers() {
+    take_events();
+    GREETER.write().unwrap().add_configurator(greet_v1);
+    let mut ecs = EcsContainer::create()
+        .add_module(&GREETER)
+        .configure_in_test(|world| {
+            world.add_global_signal_handler::<Tick>("host", |_| log("host"));
+        })
+        .seal();
+    ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Guard {});
+    });
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["hello v1", "host"]);
+
+    let greeter = ecs.module_switch(&GREETER).unwrap();
+    greeter.disable();
+    let mut module = GREETER.write().unwrap();
+    module.clear();
+    module.add_configurator(greet_v2);
+    drop(module);
+    ecs.replace_module(&GREETER);
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["host"]);
+
+    greeter.enable();
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["host", "hello v2", "salute"]);
+}
+
+#[test]
+fn removed_module_handlers_not_invoked() {
+    take_events();
+    GUARDS.write().unwrap().add_configurator(guard);
+    let mut ecs = EcsContainer::create().add_module(&GUARDS).seal();
+    ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Guard {});
+    });
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["appear", "patrol"]);
+
+    ecs.remove_module(&GUARDS);
+    ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Guard {});
+    });
+    tick(&mut ecs);
+
+    assert_eq!(take_events(), Vec::<String>::new());
+    assert!(ecs.module_switch(&GUARDS).is_none());
+}
+
+#[test]
+fn replaced_module_aggregate_and_index_include_existing_entities() {
+    CENSUS.write().unwrap().add_configurator(census);
+    let mut ecs = EcsContainer::create().add_module(&CENSUS).seal();
+    spawn_guard(&mut ecs, 1);
+    spawn_guard(&mut ecs, 2);
+
+    ecs.replace_module(&CENSUS);
+    assert_eq!(census_of(&mut ecs, 2), (2, 1));
+
+    spawn_guard(&mut ecs, 2);
+    assert_eq!(census_of(&mut ecs, 2), (3, 2));
+}
+
+#[test]
+fn removed_module_aggregate_not_updated() {
+    RETIRED_CENSUS.write().unwrap().add_configurator(census);
+    let mut ecs = EcsContainer::create().add_module(&RETIRED_CENSUS).seal();
+    spawn_guard(&mut ecs, 1);
+
+    ecs.remove_module(&RETIRED_CENSUS);
+    spawn_guard(&mut ecs, 1);
+
+    let (_, result) = ecs.execute_once("census", |ctx| {
+        ctx.aggregate::<GuardCount>();
+    });
+    assert_eq!(result.errors.len(), 1);
+}
+
+#[test]
+fn configurator_panicked_on_replace_leaves_world_intact() {
+    take_events();
+    FLAKY.write().unwrap().add_configurator(flaky);
+    let mut ecs = EcsContainer::create()
+        .add_module(&FLAKY)
+        .configure_in_test(|world| {
+            world.add_global_signal_handler::<Tick>("host", |_| log("host"));
+        })
+        .seal();
+    ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Guard {});
+    });
+
+    BROKEN.set(true);
+    let replaced = panic::catch_unwind(AssertUnwindSafe(|| ecs.replace_module(&FLAKY)));
+    BROKEN.set(false);
+    assert!(replaced.is_err());
+
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["host"]);
+
+    ecs.replace_module(&FLAKY);
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["host", "patrol"]);
+}
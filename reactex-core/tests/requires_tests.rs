@@ -0,0 +1,130 @@
+mod common;
+
+use common::get;
+use common::has;
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_macro::EcsComponent;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+#[derive(EcsComponent, Debug, Default, Clone, Eq, PartialEq)]
+#[ecs(requires(Transform, Velocity))]
+struct RigidBody {}
+
+#[derive(EcsComponent, Debug, Default, Clone, Eq, PartialEq)]
+#[ecs(requires(Parent))]
+struct Transform {
+    x: i32,
+}
+
+#[derive(EcsComponent, Debug, Default, Clone, Eq, PartialEq)]
+struct Velocity {
+    dx: i32,
+}
+
+#[derive(EcsComponent, Debug, Default, Clone, Eq, PartialEq)]
+struct Parent {}
+
+#[test]
+fn required_components_added_with_default_values() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(RigidBody {}).key());
+
+    let entity = entity.unwrap();
+
+    assert_eq!(get(&mut ecs, entity), Some(Transform { x: 0 }));
+    assert_eq!(get(&mut ecs, entity), Some(Velocity { dx: 0 }));
+    assert!(has::<Parent>(&mut ecs, entity));
+}
+
+#[test]
+fn required_component_added_explicitly_not_replaced() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(RigidBody {})
+            .add(Transform { x: 7 })
+            .key()
+    });
+
+    assert_eq!(get(&mut ecs, entity.unwrap()), Some(Transform { x: 7 }));
+}
+
+#[test]
+fn existing_required_component_not_replaced() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(Velocity { dx: 3 }).key()
+    });
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().add(RigidBody {});
+    });
+
+    assert_eq!(get(&mut ecs, entity), Some(Velocity { dx: 3 }));
+}
+
+#[test]
+fn required_components_appear_together() {
+    let matched = Rc::new(Mutex::new(Vec::new()));
+    let mut ecs = {
+        let matched = matched.clone();
+        EcsContainer::create()
+            .configure_in_test(|world| {
+                world.add_appear_handler(
+                    "test",
+                    ecs_filter!(RigidBody, Transform, Velocity, Parent),
+                    move |_, entity| matched.lock().unwrap().push(entity),
+                )
+            })
+            .seal()
+    };
+
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(RigidBody {}).key());
+
+    assert_eq!(*matched.lock().unwrap(), vec![entity.unwrap()]);
+}
+
+#[test]
+fn required_component_removal_reported() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(RigidBody {}).key());
+    let entity = entity.unwrap();
+
+    let (_, result) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().remove::<Velocity>();
+    });
+
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].details.message.contains("Velocity"));
+    assert!(!has::<Velocity>(&mut ecs, entity));
+}
+
+#[test]
+fn required_component_removal_with_dependent_not_reported() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(RigidBody {}).key());
+    let entity = entity.unwrap();
+
+    let (_, result) = ecs.execute_once("test", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.remove::<RigidBody>();
+        entity.remove::<Velocity>();
+    });
+
+    assert!(result.errors.is_empty());
+}
+
+#[test]
+fn destroyed_entity_not_reported() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(RigidBody {}).key());
+    let entity = entity.unwrap();
+
+    let (_, result) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().destroy();
+    });
+
+    assert!(result.errors.is_empty());
+}
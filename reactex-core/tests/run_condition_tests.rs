@@ -0,0 +1,155 @@
+mod common;
+
+use common::log;
+use common::take_events;
+use common::tick;
+use common::Tick;
+use reactex_core::ecs_filter;
+use reactex_core::ecs_module;
+use reactex_core::on_signal;
+use reactex_core::on_signal_global;
+use reactex_core::ConfigurableWorld;
+use reactex_core::Ctx;
+use reactex_core::EcsComponent;
+use reactex_core::EcsContainer;
+use reactex_core::World;
+
+ecs_module!(AI);
+ecs_module!(DEBUG);
+ecs_module!(SPAWNER);
+ecs_module!(ALARM);
+ecs_module!(LIGHTS);
+
+#[derive(EcsComponent, Debug)]
+struct Guard {}
+
+#[derive(EcsComponent, Debug)]
+struct DebugMode {}
+
+#[on_signal_global(AI)]
+fn think(_ctx: Ctx<Tick>) {
+    log("think");
+}
+
+#[on_signal(AI)]
+fn patrol(_ctx: Ctx<Tick>, _guard: &Guard) {
+    log("patrol");
+}
+
+#[on_signal_global(DEBUG)]
+fn draw_gizmos(_ctx: Ctx<Tick>) {
+    log("gizmos");
+}
+
+#[test]
+fn module_and_handler_disabled_at_runtime() {
+    take_events();
+    let mut ecs = EcsContainer::create().add_module(&AI).seal();
+    ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Guard {});
+    });
+    let ai = ecs.module_switch(&AI).unwrap();
+
+    ai.disable();
+    tick(&mut ecs);
+    assert_eq!(take_events(), Vec::<String>::new());
+
+    ai.enable();
+    ecs.module_handler_switch(&AI, "think").unwrap().disable();
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["patrol"]);
+
+    assert!(ecs.module_switch(&DEBUG).is_none());
+    assert!(ecs.handler_switch("unknown").is_none());
+    assert!(ecs.handler_switch("think").is_none());
+}
+
+#[test]
+fn module_runs_only_while_condition_holds() {
+    take_events();
+    World::register_query(ecs_filter!(DebugMode));
+    DEBUG
+        .write()
+        .unwrap()
+        .run_if(|ctx| !ctx.query_is_empty(ecs_filter!(DebugMode)));
+    let mut ecs = EcsContainer::create().add_module(&DEBUG).seal();
+
+    tick(&mut ecs);
+    assert_eq!(take_events(), Vec::<String>::new());
+
+    ecs.execute_once("enable debug", |ctx| {
+        ctx.create_entity().add(DebugMode {});
+    });
+    tick(&mut ecs);
+    assert_eq!(take_events(), vec!["gizmos"]);
+}
+
+#[test]
+fn panicking_run_condition_reported_and_handler_skipped() {
+    take_events();
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_global_signal_handler::<Tick>("checked", |_| log("checked"));
+            world.run_if("checked", |_| panic!("broken condition"));
+        })
+        .seal();
+
+    let (_, result) = ecs.execute_once("tick", |ctx| ctx.send_signal(Tick));
+
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(take_events(), Vec::<String>::new());
+}
+
+fn spawn_guard(ecs: &mut EcsContainer) {
+    ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Guard {});
+    });
+}
+
+#[test]
+fn disabled_module_appear_handler_not_invoked() {
+    take_events();
+    SPAWNER
+        .write()
+        .unwrap()
+        .add_configurator(|world: &mut ConfigurableWorld| {
+            world.add_appear_handler("guard appeared", ecs_filter!(Guard), |_, _| log("appear"));
+        });
+    let mut ecs = EcsContainer::create().add_module(&SPAWNER).seal();
+    let spawner = ecs.module_switch(&SPAWNER).unwrap();
+
+    spawner.disable();
+    spawn_guard(&mut ecs);
+    assert_eq!(take_events(), Vec::<String>::new());
+
+    spawner.enable();
+    spawn_guard(&mut ecs);
+    assert_eq!(take_events(), vec!["appear"]);
+}
+
+#[test]
+fn same_named_handlers_of_different_modules_switched_separately() {
+    take_events();
+    ALARM
+        .write()
+        .unwrap()
+        .add_configurator(|world: &mut ConfigurableWorld| {
+            world.add_appear_handler("on guard", ecs_filter!(Guard), |_, _| log("alarm"));
+        });
+    LIGHTS
+        .write()
+        .unwrap()
+        .add_configurator(|world: &mut ConfigurableWorld| {
+            world.add_appear_handler("on guard", ecs_filter!(Guard), |_, _| log("lights"));
+        });
+    let mut ecs = EcsContainer::create()
+        .add_module(&ALARM)
+        .add_module(&LIGHTS)
+        .seal();
+
+    ecs.module_handler_switch(&ALARM, "on guard")
+        .unwrap()
+        .disable();
+    spawn_guard(&mut ecs);
+    assert_eq!(take_events(), vec!["lights"]);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::get;
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_core::World;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Level {}
+
+fn spawn_level(ecs: &mut EcsContainer, count: i32) -> Vec<EntityKey> {
+    let (keys, _) = ecs.execute_once("spawn", move |ctx| {
+        (0..count)
+            .map(|i| ctx.create_entity().add(A { value: i }).add(Level {}).key())
+            .collect::<Vec<_>>()
+    });
+    keys.unwrap()
+}
+
+fn unload_level(ecs: &mut EcsContainer) {
+    ecs.execute_once("unload", |ctx| ctx.destroy_all(ecs_filter!(Level)));
+}
+
+#[test]
+fn memory_reclaimed_after_level_unload() {
+    World::register_query(ecs_filter!(Level));
+    let mut ecs = EcsContainer::create().seal();
+    let (player, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(A { value: -1 }).key());
+    let player = player.unwrap();
+    let initial_capacity = ecs.entity_capacity();
+    spawn_level(&mut ecs, 10_000);
+    unload_level(&mut ecs);
+
+    ecs.shrink_to_fit();
+
+    assert_eq!(ecs.entity_capacity(), initial_capacity);
+    assert_eq!(get(&mut ecs, player), Some(A { value: -1 }));
+}
+
+#[test]
+fn stale_keys_not_valid_after_shrink() {
+    World::register_query(ecs_filter!(Level));
+    let mut ecs = EcsContainer::create().seal();
+    let level = spawn_level(&mut ecs, 2_000);
+    unload_level(&mut ecs);
+    ecs.shrink_to_fit();
+
+    let next_level = spawn_level(&mut ecs, 2_000);
+
+    assert!(level.iter().all(|it| get::<A>(&mut ecs, *it).is_none()));
+    assert_eq!(get(&mut ecs, next_level[1_999]), Some(A { value: 1_999 }));
+}
+
+#[test]
+fn stale_keys_rejected_right_after_shrink() {
+    World::register_query(ecs_filter!(Level));
+    let mut ecs = EcsContainer::create().seal();
+    let level = spawn_level(&mut ecs, 2_000);
+    unload_level(&mut ecs);
+    ecs.shrink_to_fit();
+
+    let (result, _) = ecs.execute_once("read", move |ctx| {
+        level.iter().all(|it| ctx.get_entity(*it).is_none())
+    });
+
+    assert_eq!(result, Some(true));
+}
+
+#[test]
+fn automatically_shrunk_if_enabled() {
+    World::register_query(ecs_filter!(Level));
+    let mut ecs = EcsContainer::create().auto_shrink().seal();
+    let initial_capacity = ecs.entity_capacity();
+    spawn_level(&mut ecs, 10_000);
+    assert!(ecs.entity_capacity() > initial_capacity);
+
+    unload_level(&mut ecs);
+
+    assert_eq!(ecs.entity_capacity(), initial_capacity);
+}
+
+#[test]
+fn not_shrunk_automatically_by_default() {
+    World::register_query(ecs_filter!(Level));
+    let mut ecs = EcsContainer::create().seal();
+    spawn_level(&mut ecs, 10_000);
+    let capacity = ecs.entity_capacity();
+
+    unload_level(&mut ecs);
+
+    assert_eq!(ecs.entity_capacity(), capacity);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "spatial")]
+
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_core::World;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(EcsComponent, Debug)]
+struct Enemy {}
+
+fn container() -> EcsContainer {
+    World::register_query(ecs_filter!(Position));
+    World::register_query(ecs_filter!(Enemy, Position));
+    EcsContainer::create()
+        .configure_in_test(|world| {
+            world.register_spatial_index::<Position, 2>(4.0, |it| [it.x, it.y]);
+        })
+        .seal()
+}
+
+fn spawn(ecs: &mut EcsContainer, points: &'static [(f32, f32, bool)]) -> Vec<EntityKey> {
+    let (entities, _) = ecs.execute_once("spawn", |ctx| {
+        points
+            .iter()
+            .map(|&(x, y, enemy)| {
+                let entity = ctx.create_entity().add(Position { x, y });
+                if enemy {
+                    entity.add(Enemy {}).key()
+                } else {
+                    entity.key()
+                }
+            })
+            .collect()
+    });
+    entities.unwrap()
+}
+
+fn within_radius(ecs: &mut EcsContainer, center: [f32; 2], radius: f32) -> Vec<EntityKey> {
+    let (result, _) = ecs.execute_once("query", move |ctx| {
+        let mut entities: Vec<_> = ctx
+            .query_radius(center, radius, ecs_filter!(Enemy, Position))
+            .map(|it| it.key())
+            .collect();
+        entities.sort_by_key(|it| it.to_string());
+        entities
+    });
+    result.unwrap()
+}
+
+fn sorted(mut entities: Vec<EntityKey>) -> Vec<EntityKey> {
+    entities.sort_by_key(|it| it.to_string());
+    entities
+}
+
+#[test]
+fn entities_found_within_radius_and_filter() {
+    let mut ecs = container();
+    let entities = spawn(
+        &mut ecs,
+        &[(0.0, 0.0, true), (3.0, 4.0, true), (3.0, 4.1, true), (1.0, 1.0, false)],
+    );
+
+    let result = within_radius(&mut ecs, [0.0, 0.0], 5.0);
+
+    assert_eq!(result, sorted(vec![entities[0], entities[1]]));
+}
+
+#[test]
+fn entities_found_within_box() {
+    let mut ecs = container();
+    let entities = spawn(
+        &mut ecs,
+        &[(-10.0, 2.0, false), (10.0, 2.0, true), (11.0, 2.0, true), (0.0, 20.0, false)],
+    );
+
+    let (result, _) = ecs.execute_once("query", |ctx| {
+        ctx.query_aabb([-10.0, 0.0], [10.0, 5.0], ecs_filter!(Position))
+            .map(|it| it.key())
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(sorted(result.unwrap()), sorted(vec![entities[0], entities[1]]));
+}
+
+#[test]
+fn index_follows_modification_and_removal() {
+    let mut ecs = container();
+    let entities = spawn(&mut ecs, &[(0.0, 0.0, true), (1.0, 0.0, true)]);
+    let (moved, removed) = (entities[0], entities[1]);
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(moved)
+            .unwrap()
+            .modify::<Position>(|it| it.x = 100.0);
+        ctx.get_entity(removed).unwrap().remove::<Position>();
+    });
+
+    assert!(within_radius(&mut ecs, [0.0, 0.0], 10.0).is_empty());
+    assert_eq!(within_radius(&mut ecs, [100.0, 0.0], 1.0), vec![moved]);
+}
+
+#[test]
+fn uncommitted_entities_not_visible() {
+    let mut ecs = container();
+
+    let (found, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(Position { x: 0.0, y: 0.0 })
+            .add(Enemy {});
+        ctx.query_radius([0.0, 0.0], 1.0, ecs_filter!(Enemy, Position))
+            .count()
+    });
+
+    assert_eq!(found, Some(0));
+    assert_eq!(within_radius(&mut ecs, [0.0, 0.0], 1.0).len(), 1);
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "uuid")]
+
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_core::StableId;
+use reactex_macro::EcsComponent;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+fn find(ecs: &mut EcsContainer, id: StableId) -> Option<EntityKey> {
+    let (result, _) = ecs.execute_once("find", move |ctx| {
+        ctx.find_by_stable_id(id).map(|it| it.key())
+    });
+    result.unwrap()
+}
+
+#[test]
+fn entity_found_by_stable_id() {
+    let mut ecs = EcsContainer::create().seal();
+    let id = StableId::new_random();
+    let (entity, _) = ecs.execute_once("test", move |ctx| {
+        ctx.create_entity().add(id).add(A { value: 1 }).key()
+    });
+
+    assert_eq!(find(&mut ecs, id), entity);
+    assert_eq!(find(&mut ecs, StableId::new_random()), None);
+}
+
+#[test]
+fn entity_not_found_before_commit() {
+    let mut ecs = EcsContainer::create().seal();
+    let id = StableId::new_random();
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        ctx.create_entity().add(id);
+        ctx.find_by_stable_id(id).is_some()
+    });
+
+    assert_eq!(result, Some(false));
+}
+
+#[test]
+fn entity_not_found_after_destroy() {
+    let mut ecs = EcsContainer::create().seal();
+    let id = StableId::new_random();
+    let (entity, _) = ecs.execute_once("test", move |ctx| ctx.create_entity().add(id).key());
+    let entity = entity.unwrap();
+
+    ecs.execute_once("test", move |ctx| ctx.get_entity(entity).unwrap().destroy());
+
+    assert_eq!(find(&mut ecs, id), None);
+}
+
+#[test]
+fn entity_found_by_changed_stable_id() {
+    let mut ecs = EcsContainer::create().seal();
+    let id = StableId::new_random();
+    let new_id = StableId::new_random();
+    let (entity, _) = ecs.execute_once("test", move |ctx| ctx.create_entity().add(id).key());
+    let entity = entity.unwrap();
+
+    ecs.execute_once("test", move |ctx| ctx.get_entity(entity).unwrap().set(new_id));
+
+    assert_eq!(find(&mut ecs, id), None);
+    assert_eq!(find(&mut ecs, new_id), Some(entity));
+}
+
+#[test]
+fn clone_gets_no_stable_id() {
+    let mut ecs = EcsContainer::create().seal();
+    let id = StableId::new_random();
+    let (entity, _) = ecs.execute_once("test", move |ctx| {
+        ctx.create_entity().add(id).add(A { value: 1 }).key()
+    });
+    let entity = entity.unwrap();
+
+    let (clone, _) = ecs.execute_once("test", move |ctx| ctx.clone_entity(entity).unwrap().key());
+    let clone = clone.unwrap();
+
+    let (result, _) = ecs.execute_once("test", move |ctx| {
+        let clone = ctx.get_entity(clone).unwrap();
+        (clone.get::<A>().cloned(), clone.get::<StableId>().copied())
+    });
+    assert_eq!(result, Some((Some(A { value: 1 }), None)));
+    assert_eq!(find(&mut ecs, id), Some(entity));
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::log;
+use common::take_events;
+use reactex_core::ecs_filter;
+use reactex_core::EcsComponent;
+use reactex_core::EcsContainer;
+use reactex_core::PostUpdate;
+use reactex_core::PreUpdate;
+use reactex_core::Update;
+use reactex_core::World;
+
+#[derive(EcsComponent, Debug)]
+struct Projectile {}
+
+#[derive(Clone, Debug)]
+struct Fire;
+
+#[derive(Copy, Clone, Default, Debug)]
+struct Physics;
+
+#[test]
+fn stages_sent_in_order_with_pipeline_drained_between() {
+    take_events();
+    World::register_query(ecs_filter!(Projectile));
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_global_signal_handler::<PostUpdate>("count", |ctx| {
+                log(format!(
+                    "post update, {} projectiles",
+                    ctx.query_count(ecs_filter!(Projectile))
+                ));
+            });
+            world.add_global_signal_handler::<Fire>("fire", |ctx| {
+                log("fire");
+                ctx.create_entity().add(Projectile {});
+            });
+            world.add_global_signal_handler::<Update>("shoot", |ctx| {
+                log("update");
+                ctx.send_signal(Fire);
+            });
+            world.add_global_signal_handler::<PreUpdate>("input", |_| log("pre update"));
+        })
+        .seal();
+
+    let result = ecs.tick();
+
+    assert!(result.errors.is_empty());
+    assert_eq!(
+        take_events(),
+        vec!["pre update", "update", "fire", "post update, 1 projectiles"]
+    );
+}
+
+#[test]
+fn custom_stage_inserted_and_stages_without_handlers_skipped() {
+    take_events();
+    let mut ecs = EcsContainer::create()
+        .stages(|stages| {
+            stages.insert_after::<Physics, Update>();
+            stages.remove::<PreUpdate>();
+        })
+        .configure_in_test(|world| {
+            world.add_global_signal_handler::<PreUpdate>("input", |_| log("pre update"));
+            world.add_global_signal_handler::<Physics>("physics", |_| log("physics"));
+            world.add_global_signal_handler::<PostUpdate>("late", |_| log("post update"));
+        })
+        .seal();
+
+    ecs.tick();
+    ecs.tick();
+
+    assert_eq!(
+        take_events(),
+        vec!["physics", "post update", "physics", "post update"]
+    );
+}
+
+#[test]
+#[should_panic(expected = "is added twice")]
+fn stage_added_twice_rejected() {
+    EcsContainer::create().stages(|stages| stages.push::<Update>());
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use common::log;
+use common::take_events;
+use reactex_core::ecs_module;
+use reactex_core::on_enter;
+use reactex_core::on_exit;
+use reactex_core::EcsComponent;
+use reactex_core::EcsContainer;
+use reactex_core::EcsState;
+use reactex_core::Entity;
+use reactex_core::EntityKey;
+
+ecs_module!(AI);
+
+#[derive(EcsState, Debug)]
+enum Ai {
+    Idle,
+    Patrol { waypoint: u32 },
+    Alert,
+    Chase,
+}
+
+#[derive(EcsComponent, Debug)]
+struct Guard {}
+
+#[on_exit(AI, Ai::Idle)]
+fn exit_idle(ai: &Ai) {
+    log(format!("exit idle, state {:?}", ai));
+}
+
+#[on_enter(AI, Ai::Patrol)]
+fn enter_patrol(ai: &Ai) {
+    if let Ai::Patrol { waypoint } = ai {
+        log(format!("enter patrol, waypoint {}", waypoint));
+    }
+}
+
+#[on_exit(AI, Ai::Patrol)]
+fn exit_patrol(_entity: Entity) {
+    log("exit patrol".to_string());
+}
+
+#[on_enter(AI, Ai::Alert)]
+fn enter_alert(entity: Entity) {
+    log("enter alert".to_string());
+    entity.transition(Ai::Chase);
+}
+
+#[on_exit(AI, Ai::Alert)]
+fn exit_alert(_entity: Entity) {
+    log("exit alert".to_string());
+}
+
+#[on_enter(AI, Ai::Chase)]
+fn enter_chase(_guard: &Guard, ai: &Ai) {
+    log(format!("guard enter chase, state {:?}", ai));
+}
+
+fn container() -> EcsContainer {
+    take_events();
+    EcsContainer::create().add_module(&AI).seal()
+}
+
+fn spawn(ecs: &mut EcsContainer, guard: bool) -> EntityKey {
+    let (entity, _) = ecs.execute_once("spawn", move |ctx| {
+        let entity = ctx.create_entity().add(Ai::Idle);
+        let entity = if guard { entity.add(Guard {}) } else { entity };
+        entity.key()
+    });
+    take_events();
+    entity.unwrap()
+}
+
+fn transition(ecs: &mut EcsContainer, entity: EntityKey, state: Ai) {
+    ecs.execute_once("transition", move |ctx| {
+        ctx.get_entity(entity).unwrap().transition(state);
+    });
+}
+
+#[test]
+fn exit_fired_before_enter() {
+    let mut ecs = container();
+    let entity = spawn(&mut ecs, false);
+
+    transition(&mut ecs, entity, Ai::Patrol { waypoint: 3 });
+
+    assert_eq!(
+        take_events(),
+        vec!["exit idle, state Idle", "enter patrol, waypoint 3",]
+    );
+}
+
+#[test]
+fn added_and_destroyed_states_entered_and_exited() {
+    let mut ecs = container();
+
+    let (entity, _) = ecs.execute_once("spawn", |ctx| {
+        ctx.create_entity().add(Ai::Patrol { waypoint: 1 }).key()
+    });
+    let entity = entity.unwrap();
+    assert_eq!(take_events(), vec!["enter patrol, waypoint 1"]);
+
+    ecs.execute_once("destroy", move |ctx| {
+        ctx.get_entity(entity).unwrap().destroy();
+    });
+    assert_eq!(take_events(), vec!["exit patrol"]);
+}
+
+#[test]
+fn transitions_chained_in_single_transaction() {
+    let mut ecs = container();
+    let guard = spawn(&mut ecs, true);
+    let other = spawn(&mut ecs, false);
+
+    transition(&mut ecs, guard, Ai::Alert);
+    transition(&mut ecs, other, Ai::Alert);
+
+    assert_eq!(
+        take_events(),
+        vec![
+            "exit idle, state Idle",
+            "enter alert",
+            "exit alert",
+            "guard enter chase, state Chase",
+            "exit idle, state Idle",
+            "enter alert",
+            "exit alert",
+        ]
+    );
+}
+
+#[test]
+fn transitions_in_single_handler_collapsed_to_last() {
+    let mut ecs = container();
+    let entity = spawn(&mut ecs, true);
+
+    ecs.execute_once("transition twice", move |ctx| {
+        let entity = ctx.get_entity(entity).unwrap();
+        entity.transition(Ai::Patrol { waypoint: 3 });
+        entity.transition(Ai::Chase);
+    });
+
+    assert_eq!(
+        take_events(),
+        vec!["exit idle, state Idle", "guard enter chase, state Chase"]
+    );
+}
+
+#[test]
+fn transition_without_state_ignored() {
+    let mut ecs = container();
+    let (entity, _) = ecs.execute_once("spawn", |ctx| ctx.create_entity().add(Guard {}).key());
+
+    transition(&mut ecs, entity.unwrap(), Ai::Alert);
+
+    assert!(take_events().is_empty());
+    let (state, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity.unwrap())
+            .unwrap()
+            .get::<Ai>()
+            .is_some()
+    });
+    assert_eq!(state, Some(false));
+}
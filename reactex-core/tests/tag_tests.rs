@@ -0,0 +1,124 @@
+mod common;
+
+use common::get;
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_core::World;
+use reactex_macro::EcsComponent;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Selected;
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct Dead {}
+
+#[derive(EcsComponent, Debug, Clone, Eq, PartialEq)]
+struct A {
+    value: i32,
+}
+
+#[test]
+fn tags_added_to_many_entities() {
+    let mut ecs = EcsContainer::create().seal();
+    let (keys, _) = ecs.execute_once("test", |ctx| {
+        (0..100)
+            .map(|_| ctx.create_entity().add(Selected).add(Dead {}).key())
+            .collect::<Vec<_>>()
+    });
+
+    for key in keys.unwrap() {
+        assert_eq!(get(&mut ecs, key), Some(Selected));
+        assert_eq!(get(&mut ecs, key), Some(Dead {}));
+    }
+}
+
+#[test]
+fn tag_removed_from_one_entity_only() {
+    let mut ecs = EcsContainer::create().seal();
+    let (keys, _) = ecs.execute_once("test", |ctx| {
+        let first = ctx.create_entity().add(Selected).key();
+        let second = ctx.create_entity().add(Selected).key();
+        (first, second)
+    });
+    let (first, second) = keys.unwrap();
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(first).unwrap().remove::<Selected>();
+    });
+
+    assert_eq!(get::<Selected>(&mut ecs, first), None);
+    assert_eq!(get(&mut ecs, second), Some(Selected));
+}
+
+#[test]
+fn tag_added_again_after_removal_from_all_entities() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(Selected).key());
+    let entity = entity.unwrap();
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().remove::<Selected>();
+    });
+
+    ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(entity).unwrap().add(Selected);
+    });
+
+    assert_eq!(get(&mut ecs, entity), Some(Selected));
+}
+
+#[test]
+fn tags_matched_by_filters() {
+    World::register_query(ecs_filter!(A, Dead));
+    let mut ecs = EcsContainer::create().seal();
+    ecs.execute_once("test", |ctx| {
+        ctx.create_entity().add(A { value: 1 }).add(Dead {});
+        ctx.create_entity().add(A { value: 2 });
+    });
+
+    let (result, _) = ecs.execute_once("test", |ctx| {
+        ctx.query(ecs_filter!(A, Dead))
+            .map(|it| it.get::<A>().unwrap().value)
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(result, Some(vec![1]));
+}
+
+#[test]
+fn tagged_entity_cloned() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(Selected).key());
+    let (clone, _) = ecs.execute_once("test", move |ctx| {
+        ctx.clone_entity(entity.unwrap()).unwrap().key()
+    });
+
+    assert_eq!(get(&mut ecs, clone.unwrap()), Some(Selected));
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Pong;
+
+#[test]
+fn zero_sized_signal_sent_twice_in_one_transaction() {
+    let received = Rc::new(Mutex::new(0));
+    let mut ecs = {
+        let received = received.clone();
+        EcsContainer::create()
+            .configure_in_test(|world| {
+                world.add_global_signal_handler::<Pong>("pong", move |_| {
+                    *received.lock().unwrap() += 1
+                });
+            })
+            .seal()
+    };
+
+    let (_, result) = ecs.execute_once("test", |ctx| {
+        ctx.send_signal(Pong);
+        ctx.send_signal(Pong);
+    });
+
+    assert!(result.errors.is_empty());
+    assert_eq!(*received.lock().unwrap(), 2);
+}
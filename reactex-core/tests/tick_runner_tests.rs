@@ -0,0 +1,137 @@
+use reactex_core::EcsContainer;
+use reactex_core::FixedUpdate;
+use reactex_core::ManualClock;
+use reactex_core::Render;
+use reactex_core::TickRunner;
+use std::cell::RefCell;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Update(u64),
+    Render(f32),
+}
+
+thread_local! {
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+fn take_events() -> Vec<Event> {
+    EVENTS.with(|it| it.take())
+}
+
+fn container() -> EcsContainer {
+    take_events();
+    EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_global_signal_handler::<FixedUpdate>("update", |ctx| {
+                assert_eq!(ctx.signal.dt, Duration::from_millis(10));
+                EVENTS.with(|it| it.borrow_mut().push(Event::Update(ctx.signal.tick)));
+            });
+            world.add_global_signal_handler::<Render>("render", |ctx| {
+                let alpha = (ctx.signal.alpha * 100.0).round() / 100.0;
+                EVENTS.with(|it| it.borrow_mut().push(Event::Render(alpha)));
+            });
+        })
+        .seal()
+}
+
+fn runner(clock: &ManualClock) -> TickRunner<ManualClock> {
+    TickRunner::with_clock(clock.clone(), Duration::from_millis(10))
+}
+
+#[test]
+fn fixed_updates_accumulated_across_frames() {
+    let mut ecs = container();
+    let clock = ManualClock::new();
+    let mut runner = runner(&clock);
+
+    runner.frame(&mut ecs);
+    clock.advance(Duration::from_millis(25));
+    runner.frame(&mut ecs);
+    clock.advance(Duration::from_millis(7));
+    runner.frame(&mut ecs);
+
+    assert_eq!(
+        take_events(),
+        vec![
+            Event::Render(0.0),
+            Event::Update(1),
+            Event::Update(2),
+            Event::Render(0.5),
+            Event::Update(3),
+            Event::Render(0.2),
+        ]
+    );
+}
+
+#[test]
+fn catch_up_limited() {
+    let mut ecs = container();
+    let clock = ManualClock::new();
+    let mut runner = runner(&clock).max_catch_up(3);
+    runner.frame(&mut ecs);
+    take_events();
+
+    clock.advance(Duration::from_millis(1004));
+    runner.frame(&mut ecs);
+
+    assert_eq!(
+        take_events(),
+        vec![
+            Event::Update(1),
+            Event::Update(2),
+            Event::Update(3),
+            Event::Render(0.4),
+        ]
+    );
+    assert_eq!(runner.tick(), 3);
+}
+
+#[test]
+fn paused_runner_updates_only_requested_steps() {
+    let mut ecs = container();
+    let clock = ManualClock::new();
+    let mut runner = runner(&clock);
+    runner.frame(&mut ecs);
+    take_events();
+
+    runner.pause();
+    clock.advance(Duration::from_millis(50));
+    runner.frame(&mut ecs);
+    runner.step(1);
+    clock.advance(Duration::from_millis(50));
+    runner.frame(&mut ecs);
+    runner.resume();
+    clock.advance(Duration::from_millis(10));
+    runner.frame(&mut ecs);
+
+    assert_eq!(
+        take_events(),
+        vec![
+            Event::Render(0.0),
+            Event::Update(1),
+            Event::Render(0.0),
+            Event::Update(2),
+            Event::Render(0.0),
+        ]
+    );
+}
+
+#[test]
+fn loop_runs_until_stopped() {
+    let mut ecs = EcsContainer::create().seal();
+    let clock = ManualClock::new();
+    let mut runner = runner(&clock);
+    let mut frames = 0;
+
+    ecs.run_loop(&mut runner, |_, runner, result| {
+        assert!(result.errors.is_empty());
+        frames += 1;
+        clock.advance(Duration::from_millis(10));
+        runner.tick() < 4
+    });
+
+    assert_eq!(frames, 5);
+    assert_eq!(runner.tick(), 4);
+}
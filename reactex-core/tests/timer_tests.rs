@@ -0,0 +1,140 @@
+use reactex_core::ecs_filter;
+use reactex_core::EcsContainer;
+use reactex_core::EntityKey;
+use reactex_core::TimeToLive;
+use reactex_core::Timer;
+use reactex_macro::EcsComponent;
+use std::sync::Mutex;
+
+#[derive(EcsComponent, Debug)]
+struct Cooldown {}
+
+#[derive(Clone, Debug)]
+struct CooldownFinished {
+    ability: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Tick;
+
+fn container(received: &'static Mutex<Vec<(EntityKey, u32)>>) -> EcsContainer {
+    EcsContainer::create()
+        .configure_in_test(move |world| {
+            world.add_entity_signal_handler::<CooldownFinished>(
+                "cooldown_finished",
+                ecs_filter!(Cooldown),
+                move |ctx, entity| {
+                    received.lock().unwrap().push((entity, ctx.signal.ability));
+                },
+            );
+        })
+        .seal()
+}
+
+fn idle(ecs: &mut EcsContainer, transactions: usize) {
+    for _ in 0..transactions {
+        ecs.execute_once("idle", |_| {});
+    }
+}
+
+fn exists(ecs: &mut EcsContainer, entity: EntityKey) -> bool {
+    let (result, _) = ecs.execute_once("exists", move |ctx| ctx.get_entity(entity).is_some());
+    result.unwrap()
+}
+
+#[test]
+fn timer_sends_signal_to_its_entity_on_expiry() {
+    static RECEIVED: Mutex<Vec<(EntityKey, u32)>> = Mutex::new(Vec::new());
+    let mut ecs = container(&RECEIVED);
+    let (entities, _) = ecs.execute_once("test", |ctx| {
+        let timer = ctx
+            .create_entity()
+            .add(Cooldown {})
+            .add(Timer::new(3, CooldownFinished { ability: 7 }))
+            .key();
+        let other = ctx.create_entity().add(Cooldown {}).key();
+        (timer, other)
+    });
+    let (timer, _) = entities.unwrap();
+
+    idle(&mut ecs, 2);
+    assert!(RECEIVED.lock().unwrap().is_empty());
+    idle(&mut ecs, 1);
+
+    assert_eq!(*RECEIVED.lock().unwrap(), vec![(timer, 7)]);
+    let (has_timer, _) = ecs.execute_once("test", move |ctx| {
+        ctx.get_entity(timer).unwrap().get::<Timer>().is_some()
+    });
+    assert_eq!(has_timer, Some(false));
+}
+
+#[test]
+fn time_to_live_destroys_entity() {
+    let mut ecs = EcsContainer::create().seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(Cooldown {})
+            .add(TimeToLive(2))
+            .key()
+    });
+    let entity = entity.unwrap();
+
+    ecs.execute_once("idle", |_| {});
+    assert!(exists(&mut ecs, entity));
+    ecs.execute_once("idle", |_| {});
+
+    assert!(!exists(&mut ecs, entity));
+}
+
+#[test]
+fn timers_advanced_on_tick_signal() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| world.advance_timers_on::<Tick>())
+        .seal();
+    let (entity, _) = ecs.execute_once("test", |ctx| ctx.create_entity().add(TimeToLive(2)).key());
+    let entity = entity.unwrap();
+
+    idle(&mut ecs, 5);
+    assert!(exists(&mut ecs, entity));
+    ecs.execute_once("tick", |ctx| {
+        ctx.send_signal(Tick);
+        ctx.send_signal(Tick);
+    });
+
+    assert!(!exists(&mut ecs, entity));
+}
+
+#[test]
+fn expiry_caused_by_setting_timer() {
+    let mut ecs = EcsContainer::create()
+        .configure_in_test(|world| {
+            world.add_disappear_handler("destroyed", ecs_filter!(Cooldown), |_, _| {
+                panic!("report cause");
+            });
+        })
+        .seal();
+    ecs.execute_once("set_timer_by_player", |ctx| {
+        ctx.create_entity().add(Cooldown {}).add(TimeToLive(1));
+    });
+
+    let (_, result) = ecs.execute_once("idle", |_| {});
+
+    assert_eq!(result.errors.len(), 1);
+    let cause = format!("{}", result.errors[0].cause);
+    assert!(cause.contains("time_to_live"), "{}", cause);
+    assert!(cause.contains("set_timer_by_player"), "{}", cause);
+}
+
+#[test]
+fn expired_timer_without_handlers_reported() {
+    let mut ecs = EcsContainer::create().seal();
+    ecs.execute_once("test", |ctx| {
+        ctx.create_entity()
+            .add(Timer::new(0, CooldownFinished { ability: 1 }));
+    });
+
+    let (_, result) = ecs.execute_once("idle", |_| {});
+
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].details.message.contains("no handlers"));
+}
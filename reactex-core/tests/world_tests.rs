@@ -247,3 +247,59 @@ fn component_add_delete_visible_after_commit() {
     world.execute_all();
     assert!(!world.has_component::<A>(entity).unwrap());
 }
+
+#[test]
+fn component_set_not_visible_before_commit() {
+    let mut world = create_world();
+    let entity = world.create_entity();
+    world.add_component(entity, A { value: 17 }).unwrap();
+    world.execute_all();
+
+    world.set_component(entity, A { value: 42 }).unwrap();
+
+    assert_eq!(world.get_component::<A>(entity).unwrap().unwrap().value, 17);
+}
+
+#[test]
+fn component_set_visible_after_commit() {
+    let mut world = create_world();
+    let entity = world.create_entity();
+    world.add_component(entity, A { value: 17 }).unwrap();
+    world.execute_all();
+
+    world.set_component(entity, A { value: 42 }).unwrap();
+    world.execute_all();
+
+    assert_eq!(world.get_component::<A>(entity).unwrap().unwrap().value, 42);
+}
+
+#[test]
+fn component_set_and_modifications_applied_in_order() {
+    let mut world = create_world();
+    let entity = world.create_entity();
+    world.add_component(entity, W { a: 17, b: 42 }).unwrap();
+    world.execute_all();
+
+    world.modify_component::<W>(entity, |it| it.a += 1).unwrap();
+    world.set_component(entity, W { a: 1, b: 2 }).unwrap();
+    world.modify_component::<W>(entity, |it| it.b += 3).unwrap();
+    world.execute_all();
+
+    let x = world.get_component::<W>(entity).unwrap().unwrap();
+
+    assert_eq!((x.a, x.b), (1, 5));
+}
+
+#[test]
+fn component_set_ignored_if_removed() {
+    let mut world = create_world();
+    let entity = world.create_entity();
+    world.add_component(entity, A { value: 17 }).unwrap();
+    world.execute_all();
+
+    world.set_component(entity, A { value: 42 }).unwrap();
+    world.remove_component::<A>(entity).unwrap();
+    world.execute_all();
+
+    assert!(!world.has_component::<A>(entity).unwrap());
+}
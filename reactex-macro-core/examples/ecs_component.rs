@@ -15,7 +15,8 @@ fn main() {
         item,
         "source/file.rs",
         ".derive_ecs_component.examples.txt",
-    );
+    )
+    .unwrap();
     println!("{}", result);
     println!("{}", print_item(Ok(result)));
 }
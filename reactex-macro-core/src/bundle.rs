@@ -0,0 +1,61 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse2;
+use syn::parse_quote;
+use syn::spanned::Spanned;
+use syn::Error;
+use syn::Fields;
+use syn::Index;
+use syn::Member;
+use syn::Result;
+
+pub fn derive_ecs_bundle(item: TokenStream) -> Result<TokenStream> {
+    let s: syn::ItemStruct = parse2(item)?;
+    let ty = &s.ident;
+
+    if let Fields::Unit = s.fields {
+        return Err(Error::new(s.span(), "bundle must contain at least one component"));
+    }
+    let members = s
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        })
+        .collect::<Vec<_>>();
+    let field_types = s.fields.iter().map(|it| &it.ty).collect::<Vec<_>>();
+
+    // generic fields should be components too
+    let mut generics = s.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    for field_type in &field_types {
+        predicates.push(parse_quote!(#field_type: ::reactex_core::EcsComponent));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::reactex_core::EcsBundle for #ty #ty_generics #where_clause {
+            const COMPONENT_TYPES: &'static [::reactex_core::ComponentType] = &[
+                #(::reactex_core::component_type_of::<#field_types>()),*
+            ];
+
+            fn get_component(
+                &self,
+                component_type: ::reactex_core::ComponentType,
+            ) -> Option<&dyn ::std::any::Any> {
+                #(
+                    if component_type == ::reactex_core::component_type_of::<#field_types>() {
+                        return Some(&self.#members);
+                    }
+                )*
+                None
+            }
+
+            fn add_to(self, target: &mut impl ::reactex_core::BundleTarget) {
+                #(target.add(self.#members);)*
+            }
+        }
+    })
+}
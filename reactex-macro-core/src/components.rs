@@ -1,16 +1,166 @@
 use proc_macro2::TokenStream;
 use quote::format_ident;
 use quote::quote;
+use quote::ToTokens;
 use std::fs;
 use std::io::ErrorKind;
 use syn::parse2;
+use syn::parse_quote;
+use syn::spanned::Spanned;
+use syn::Attribute;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Expr;
+use syn::Member;
+use syn::Path;
+use syn::Result;
+use syn::Type;
 
-pub fn derive_ecs_component(item: TokenStream, module_path: &str, types_file: &str) -> TokenStream {
-    let s: syn::ItemStruct = parse2(item).unwrap();
-    let ty = s.ident;
-
+pub fn derive_ecs_component(
+    item: TokenStream,
+    module_path: &str,
+    types_file: &str,
+) -> Result<TokenStream> {
+    let input: DeriveInput = parse2(item)?;
+    if let Data::Union(data) = &input.data {
+        return Err(Error::new(
+            data.union_token.span,
+            "unions are not supported as components",
+        ));
+    }
+    let mut attributes = EcsAttributes::parse(&input.attrs)?;
+    attributes.index = FieldIndex::parse(&input.data)?;
+    let ty = &input.ident;
     let ty_str = format!("{}::{}", module_path, ty);
 
+    if !input.generics.params.is_empty() {
+        // every monomorphization is a separate component registered with `register_component!`,
+        // which calls back here for the attributes
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let registrations = attributes.registrations(&quote!(Self), &ty_str);
+        let indexed = attributes.index.as_ref().map(|index| {
+            let key = &index.ty;
+            let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+            where_clause
+                .predicates
+                .push(parse_quote!(Self: ::reactex_core::EcsComponent));
+            where_clause.predicates.push(parse_quote! {
+                #key: Clone
+                    + ::std::hash::Hash
+                    + Eq
+                    + ::std::fmt::Debug
+                    + ::std::panic::RefUnwindSafe
+                    + 'static
+            });
+            quote! {
+                impl #impl_generics ::reactex_core::EcsIndexed for #ty #ty_generics #where_clause {
+                    type IndexKey = #key;
+                }
+            }
+        });
+        let indexed_bound = attributes.index.as_ref().map(|index| {
+            let key = &index.ty;
+            quote!(Self: ::reactex_core::EcsIndexed<IndexKey = #key>,)
+        });
+        return Ok(quote! {
+            impl #impl_generics #ty #ty_generics #where_clause {
+                #[doc(hidden)]
+                pub fn __register_ecs_attributes(world: &mut ::reactex_core::World)
+                where
+                    Self: ::reactex_core::EcsComponent,
+                    #indexed_bound
+                {
+                    #registrations
+                }
+            }
+
+            #indexed
+        });
+    }
+
+    let registrations = attributes.registrations(&ty.to_token_stream(), &ty_str);
+    let component = implement_component(ty.to_token_stream(), &ty_str, types_file, registrations);
+    let indexed = attributes.index.as_ref().map(|index| {
+        let key = &index.ty;
+        quote! {
+            impl ::reactex_core::EcsIndexed for #ty {
+                type IndexKey = #key;
+            }
+        }
+    });
+    Ok(quote! {
+        #component
+
+        #indexed
+    })
+}
+
+pub fn derive_ecs_state(
+    item: TokenStream,
+    module_path: &str,
+    types_file: &str,
+) -> Result<TokenStream> {
+    let input: DeriveInput = parse2(item.clone())?;
+    if !matches!(input.data, Data::Enum(_)) {
+        return Err(Error::new(
+            input.ident.span(),
+            "states should be enums, which variants are the states",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "generic states are not supported",
+        ));
+    }
+    let component = derive_ecs_component(item, module_path, types_file)?;
+    let ty = &input.ident;
+    Ok(quote! {
+        #component
+
+        impl ::reactex_core::EcsState for #ty {}
+    })
+}
+
+// register_component!(Health<Player>)
+pub fn register_component(
+    item: TokenStream,
+    module_path: &str,
+    types_file: &str,
+) -> Result<TokenStream> {
+    let ty: Type = parse2(item)?;
+    let Type::Path(path) = &ty else {
+        return Err(Error::new(ty.span(), "component type expected"));
+    };
+    let Some(last_segment) = path.path.segments.last() else {
+        return Err(Error::new(ty.span(), "component type expected"));
+    };
+    if last_segment.arguments.is_empty() {
+        return Err(Error::new(
+            ty.span(),
+            "only generic components should be registered explicitly, derive EcsComponent instead",
+        ));
+    }
+    let ty_str = format!(
+        "{}::{}",
+        module_path,
+        last_segment.to_token_stream().to_string().replace(' ', "")
+    );
+    Ok(implement_component(
+        ty.to_token_stream(),
+        &ty_str,
+        types_file,
+        quote! { <#ty>::__register_ecs_attributes(world); },
+    ))
+}
+
+fn implement_component(
+    ty: TokenStream,
+    ty_str: &str,
+    types_file: &str,
+    registrations: TokenStream,
+) -> TokenStream {
     let mut lines = match fs::read_to_string(types_file) {
         Ok(s) => s
             .lines()
@@ -25,14 +175,17 @@ pub fn derive_ecs_component(item: TokenStream, module_path: &str, types_file: &s
     let index = match lines.iter().enumerate().find(|(_, it)| **it == ty_str) {
         None => {
             let index = lines.len();
-            lines.push(ty_str.clone());
+            lines.push(ty_str.to_owned());
             fs::write(types_file, lines.join("\n")).unwrap();
             index as u16
         }
         Some((index, _)) => index as u16,
     };
-    let register_type_callback = format_ident!("register_type_callback_{}", ty.to_string());
-    let register_type = format_ident!("register_type_{}", ty.to_string());
+    let path_end = ty_str.find('<').unwrap_or(ty_str.len());
+    let name_start = ty_str[..path_end].rfind("::").map(|it| it + 2).unwrap_or(0);
+    let suffix = ty_str[name_start..].replace(|it: char| !it.is_alphanumeric(), "_");
+    let register_type_callback = format_ident!("register_type_callback_{}", suffix);
+    let register_type = format_ident!("register_type_{}", suffix);
     quote! {
         impl ::reactex_core::EcsComponent for #ty {
             const NAME: &'static str = #ty_str;
@@ -45,7 +198,142 @@ pub fn derive_ecs_component(item: TokenStream, module_path: &str, types_file: &s
         }
 
         fn #register_type(world: &mut ::reactex_core::World) {
+            use ::reactex_core::ComponentProbeFallback as _;
             world.register_component::<#ty>();
+            let probe = ::reactex_core::ComponentProbe::<#ty>::new();
+            probe.register_clone(world);
+            probe.register_entity_key_mapping(world);
+            probe.register_deserializer(world);
+            #registrations
+        }
+    }
+}
+
+// #[ecs(on_add = f, on_remove = g, requires(A, B))]
+#[derive(Default)]
+struct EcsAttributes {
+    on_add: Option<Expr>,
+    on_remove: Option<Expr>,
+    requires: Vec<Path>,
+    index: Option<FieldIndex>,
+}
+
+// #[ecs(index)] or #[ecs(index(unique))] on a single struct field
+struct FieldIndex {
+    member: Member,
+    ty: Type,
+    unique: bool,
+}
+
+impl FieldIndex {
+    fn parse(data: &Data) -> Result<Option<FieldIndex>> {
+        let mut result: Option<FieldIndex> = None;
+        let fields = match data {
+            Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+            Data::Enum(data) => {
+                let field = data.variants.iter().flat_map(|it| it.fields.iter()).find(|it| {
+                    it.attrs.iter().any(|attr| attr.path().is_ident("ecs"))
+                });
+                if let Some(field) = field {
+                    return Err(Error::new(
+                        field.span(),
+                        "fields of enum variants can't be indexed",
+                    ));
+                }
+                return Ok(None);
+            }
+            Data::Union(_) => return Ok(None),
+        };
+        for (position, field) in fields.into_iter().enumerate() {
+            for attr in field.attrs.iter().filter(|it| it.path().is_ident("ecs")) {
+                attr.parse_nested_meta(|meta| {
+                    if !meta.path.is_ident("index") {
+                        return Err(meta.error("unsupported ecs field attribute"));
+                    }
+                    if result.is_some() {
+                        return Err(meta.error("only one field of a component can be indexed"));
+                    }
+                    let mut unique = false;
+                    if meta.input.peek(syn::token::Paren) {
+                        meta.parse_nested_meta(|option| {
+                            if !option.path.is_ident("unique") {
+                                return Err(option.error("unsupported index option"));
+                            }
+                            unique = true;
+                            Ok(())
+                        })?;
+                    }
+                    let member = match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(position.into()),
+                    };
+                    result = Some(FieldIndex {
+                        member,
+                        ty: field.ty.clone(),
+                        unique,
+                    });
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl EcsAttributes {
+    fn registrations(&self, ty: &TokenStream, ty_str: &str) -> TokenStream {
+        let on_add = self.on_add.as_ref().map(|hook| {
+            let name = format!("{}::on_add", ty_str);
+            quote! { world.register_on_add::<#ty>(#name, #hook); }
+        });
+        let on_remove = self.on_remove.as_ref().map(|hook| {
+            let name = format!("{}::on_remove", ty_str);
+            quote! { world.register_on_remove::<#ty>(#name, #hook); }
+        });
+        let requires = self.requires.iter().map(|required| {
+            quote! { world.register_required_component::<#ty, #required>(); }
+        });
+        let index = self.index.as_ref().map(|index| {
+            let member = &index.member;
+            let register = if index.unique {
+                quote!(register_unique_index)
+            } else {
+                quote!(register_index)
+            };
+            quote! { world.#register::<#ty>(|it| it.#member.clone()); }
+        });
+        quote! {
+            #on_add
+            #on_remove
+            #(#requires)*
+            #index
+        }
+    }
+
+    fn parse(attrs: &[Attribute]) -> Result<EcsAttributes> {
+        let mut attributes = EcsAttributes::default();
+        for attr in attrs.iter().filter(|it| it.path().is_ident("ecs")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("requires") {
+                    return meta.parse_nested_meta(|required| {
+                        attributes.requires.push(required.path);
+                        Ok(())
+                    });
+                }
+                let hook = if meta.path.is_ident("on_add") {
+                    &mut attributes.on_add
+                } else if meta.path.is_ident("on_remove") {
+                    &mut attributes.on_remove
+                } else {
+                    return Err(meta.error("unsupported ecs attribute"));
+                };
+                if hook.is_some() {
+                    return Err(meta.error("duplicate hook"));
+                }
+                *hook = Some(meta.value()?.parse()?);
+                Ok(())
+            })?;
         }
+        Ok(attributes)
     }
 }
@@ -1,6 +1,7 @@
 #![feature(stmt_expr_attributes)]
 #![feature(proc_macro_hygiene)]
 
+pub mod bundle;
 pub mod common;
 pub mod components;
 pub mod lab_helper;
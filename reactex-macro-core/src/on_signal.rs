@@ -18,6 +18,8 @@ pub enum EventType {
     OnSignalGlobal,
     OnAppear,
     OnDisappear,
+    OnEnter,
+    OnExit,
 }
 
 pub fn on_signal(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
@@ -29,7 +31,7 @@ pub fn on_event(
     item: TokenStream,
     event_type: EventType,
 ) -> Result<TokenStream> {
-    let user_function = analyze_user_function(attr, item.clone())?;
+    let user_function = analyze_user_function(attr, item.clone(), event_type)?;
 
     let registration = generate_registration_new(user_function, event_type)?;
 
@@ -41,13 +43,40 @@ pub fn on_event(
 
 struct UserFunction {
     ecs_module_var_path: ExprPath,
+    // State::X of #[on_enter(MODULE, State::X)]
+    state_variant: Option<Path>,
     args: Vec<Argument>,
     ident: Ident,
     args_span: Span,
 }
 
-fn analyze_user_function(attr: TokenStream, item: TokenStream) -> Result<UserFunction> {
-    let ecs_module_var_path = parse2::<ExprPath>(attr)?;
+fn analyze_user_function(
+    attr: TokenStream,
+    item: TokenStream,
+    event_type: EventType,
+) -> Result<UserFunction> {
+    let (ecs_module_var_path, state_variant) = match event_type {
+        EventType::OnEnter | EventType::OnExit => {
+            let parser = |input: parse::ParseStream| {
+                let module = input.parse::<ExprPath>()?;
+                input.parse::<Token![,]>()?;
+                let variant = input.parse::<Path>()?;
+                Ok((module, variant))
+            };
+            let (module, variant) = parse::Parser::parse2(parser, attr)?;
+            if variant.segments.len() < 2 {
+                return Err(Error::new(
+                    variant.span(),
+                    "state variant expected, e.g. State::Idle",
+                ));
+            }
+            (module, Some(variant))
+        }
+        EventType::OnSignal
+        | EventType::OnSignalGlobal
+        | EventType::OnAppear
+        | EventType::OnDisappear => (parse2::<ExprPath>(attr)?, None),
+    };
 
     let function = parse2::<ItemFn>(item)
         .map_err(|err| Error::new(err.span(), "attribute is applicable only to functions"))?;
@@ -68,6 +97,7 @@ fn analyze_user_function(attr: TokenStream, item: TokenStream) -> Result<UserFun
         args_span,
         ident,
         ecs_module_var_path,
+        state_variant,
         args,
     })
 }
@@ -194,13 +224,27 @@ fn generate_registration_new(
                 ));
             }
         }
-        EventType::OnSignal | EventType::OnAppear | EventType::OnDisappear => {}
+        EventType::OnSignal
+        | EventType::OnAppear
+        | EventType::OnDisappear
+        | EventType::OnEnter
+        | EventType::OnExit => {}
     }
 
+    let state_type = user_function.state_variant.as_ref().map(|variant| {
+        let mut state_type = variant.clone();
+        state_type.segments.pop();
+        state_type.segments.pop_punct();
+        state_type
+    });
     let filter_key = match event_type {
         EventType::OnSignal | EventType::OnAppear | EventType::OnDisappear => {
             Some(ecs_filter_expression(user_function.args.iter()))
         }
+        EventType::OnEnter | EventType::OnExit => Some(state_filter_expression(
+            user_function.args.iter(),
+            state_type.as_ref().unwrap(),
+        )),
         EventType::OnSignalGlobal => None,
     };
     let function_name = &user_function.ident;
@@ -257,7 +301,11 @@ fn generate_registration_new(
                 });
             aggregate_errors(errors)?;
         }
-        EventType::OnSignal | EventType::OnAppear | EventType::OnDisappear => {
+        EventType::OnSignal
+        | EventType::OnAppear
+        | EventType::OnDisappear
+        | EventType::OnEnter
+        | EventType::OnExit => {
             let entity_or_component_args_present =
                 user_function.args.iter().any(|Argument(_, ty)| match ty {
                     ArgumentType::Ctx(_, _) => false,
@@ -322,7 +370,7 @@ fn generate_registration_new(
             };
             Some(signal_type)
         }
-        EventType::OnAppear | EventType::OnDisappear => {
+        EventType::OnAppear | EventType::OnDisappear | EventType::OnEnter | EventType::OnExit => {
             if let Some((span, signal_type)) = signal_type {
                 if signal_type.is_some() {
                     return Err(Error::new(
@@ -386,6 +434,29 @@ fn generate_registration_new(
                 world.add_disappear_handler(stringify!(#function_name), #filter_key, wrapper);
             }
         }
+        EventType::OnEnter | EventType::OnExit => {
+            let register = match event_type {
+                EventType::OnEnter => quote!(add_state_enter_handler),
+                _ => quote!(add_state_exit_handler),
+            };
+            let state_variant = &user_function.state_variant;
+            quote! {
+                fn wrapper(
+                    __ctx__: reactex_core::Ctx,
+                    entity: reactex_core::EntityKey,
+                ) {
+                    let __entity__ = __ctx__.get_entity(entity).unwrap_or_else(|| panic!("entity not found: {}", entity));
+                    #argument_mappings
+                    #function_name(#function_args);
+                }
+                world.#register::<#state_type>(
+                    stringify!(#function_name),
+                    #filter_key,
+                    |state| matches!(state, #state_variant { .. }),
+                    wrapper,
+                );
+            }
+        }
     };
     let ecs_module_path = user_function.ecs_module_var_path;
     Ok(quote! {
@@ -399,6 +470,27 @@ fn generate_registration_new(
     })
 }
 
+// the state component is a part of the filter even if it isn't requested as an argument
+fn state_filter_expression<'a>(
+    iter: impl Iterator<Item = &'a Argument>,
+    state_type: &Path,
+) -> TokenStream {
+    let state_type_str = state_type.to_token_stream().to_string();
+    let components = iter
+        .filter_map(|Argument(_, ty)| match ty {
+            ArgumentType::ComponentReference(it) | ArgumentType::ComponentMutableWrapper(it) => {
+                Some(it.to_token_stream())
+            }
+            _ => None,
+        })
+        .filter(|it| it.to_string() != state_type_str)
+        .chain([state_type.to_token_stream()]);
+    let components: Punctuated<TokenStream, Comma> = Punctuated::from_iter(components);
+    quote! {
+        ::reactex_core::ecs_filter!(#components)
+    }
+}
+
 pub(crate) fn ecs_filter_expression<'a>(iter: impl Iterator<Item = &'a Argument>) -> TokenStream {
     let components = iter.filter_map(|Argument(_, ty)| match ty {
         ArgumentType::Ctx(_, _) => None,
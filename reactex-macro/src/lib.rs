@@ -48,22 +48,61 @@ pub fn on_signal(attr: TokenStream, item: TokenStream) -> TokenStream {
         .into()
 }
 
-#[proc_macro_derive(EcsComponent)]
-pub fn derive_ecs_component(item: TokenStream) -> TokenStream {
-    let module_path = resolve_module_path();
+#[proc_macro_attribute]
+pub fn on_enter(attr: TokenStream, item: TokenStream) -> TokenStream {
+    reactex_macro_core::on_signal::on_event(attr.into(), item.into(), EventType::OnEnter)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    let file = match module_path {
-        Err(_) => ".derive_ecs_component.ide.txt",
-        Ok(_) => ".derive_ecs_component.macro.txt",
-    };
-    let module_path = match module_path {
-        Ok(it) => it,
-        Err(it) => it,
-    };
+#[proc_macro_attribute]
+pub fn on_exit(attr: TokenStream, item: TokenStream) -> TokenStream {
+    reactex_macro_core::on_signal::on_event(attr.into(), item.into(), EventType::OnExit)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(EcsComponent, attributes(ecs))]
+pub fn derive_ecs_component(item: TokenStream) -> TokenStream {
+    let (module_path, file) = resolve_component_types_file();
     reactex_macro_core::components::derive_ecs_component(item.into(), module_path.as_str(), file)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Enum component, which variant changes are requested with `entity.transition(..)`
+/// and observed with `#[on_enter(MODULE, State::X)]` / `#[on_exit(MODULE, State::X)]`.
+#[proc_macro_derive(EcsState, attributes(ecs))]
+pub fn derive_ecs_state(item: TokenStream) -> TokenStream {
+    let (module_path, file) = resolve_component_types_file();
+    reactex_macro_core::components::derive_ecs_state(item.into(), module_path.as_str(), file)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Registers a monomorphization of a generic component, e.g. `register_component!(Health<Player>)`.
+#[proc_macro]
+pub fn register_component(item: TokenStream) -> TokenStream {
+    let (module_path, file) = resolve_component_types_file();
+    reactex_macro_core::components::register_component(item.into(), module_path.as_str(), file)
+        .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
 
+#[proc_macro_derive(EcsBundle)]
+pub fn derive_ecs_bundle(item: TokenStream) -> TokenStream {
+    reactex_macro_core::bundle::derive_ecs_bundle(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn resolve_component_types_file() -> (String, &'static str) {
+    match resolve_module_path() {
+        Ok(module_path) => (module_path, ".derive_ecs_component.macro.txt"),
+        Err(module_path) => (module_path, ".derive_ecs_component.ide.txt"),
+    }
+}
+
 fn resolve_module_path() -> Result<String, String> {
     let module_path_macro_call =
         TokenStream::from_str("module_path!()").map_err(|err| err.to_string())?;
@@ -5,11 +5,14 @@ use reactex_core::ecs_module;
 use reactex_core::enable_queries;
 use reactex_core::on_appear;
 use reactex_core::on_disappear;
+use reactex_core::on_enter;
+use reactex_core::on_exit;
 use reactex_core::on_signal;
 use reactex_core::on_signal_global;
 use reactex_core::Ctx;
 use reactex_core::EcsComponent;
 use reactex_core::EcsContainer;
+use reactex_core::EcsState;
 use reactex_core::Entity;
 use reactex_core::EntityKey;
 use reactex_core::Mut;
@@ -142,6 +145,26 @@ fn system6(_entity: Entity, _a: &A, _ctx: Ctx<SomeSignal>) {
     // argument order doesn't matter
 }
 
+// enum component, which variants are states of a finite state machine.
+// switch them with `entity.transition(Door::Closed)` instead of `set`
+#[derive(EcsState)]
+enum Door {
+    Closed,
+    Open,
+}
+
+#[on_exit(DEMO, Door::Closed)]
+fn system7(_entity: Entity, _door: &Door) {
+    // called before the transition from Door::Closed (the state is still Closed here), or before
+    // the state disappears
+}
+
+#[on_enter(DEMO, Door::Open)]
+fn system8(_entity: Entity, _a: &A) {
+    // called after the transition to Door::Open (exit handlers are called first), or when the
+    // state appears. only for entities with A, the state component is matched implicitly
+}
+
 struct D {
     x: i32,
 }
@@ -166,6 +189,17 @@ fn main() {
         entity.add(A {});
     });
 
+    let (door, _) = ecs.execute_once("test", |ctx| {
+        // entity appears in Door::Closed state
+        ctx.create_entity().add(A {}).add(Door::Closed).key()
+    });
+
+    ecs.execute_once("test", |ctx| {
+        // the transition is applied atomically with the rest of the changes of the transaction
+        let door = ctx.get_entity(door.unwrap()).unwrap();
+        door.transition(Door::Open);
+    });
+
     ecs.execute_once("test", |ctx| {
         // orchestrate application using signals
         ctx.send_signal(SomeSignal);